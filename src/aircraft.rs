@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use csv::ReaderBuilder;
+use crate::load::{LoadError, LoadMode, LoadReport, Loaded};
+
+// One row of airplanes.csv
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aircraft {
+    pub name: String,
+    pub iata: String,
+    pub icao: String,
+}
+
+// Approximate maximum range in kilometers of common types, by the IATA code used in the Equipment column
+const TYPICAL_RANGES_KM: [(&str, f64); 62] = [
+    ("100", 3100.0), ("146", 2900.0), ("318", 5700.0), ("319", 6900.0), ("320", 6100.0), ("321", 5900.0),
+    ("332", 13400.0), ("333", 11700.0), ("343", 13500.0), ("345", 16600.0), ("346", 14600.0), ("380", 15200.0),
+    ("388", 15200.0), ("717", 3800.0), ("733", 4400.0), ("734", 4200.0), ("735", 4400.0), ("736", 5900.0),
+    ("73C", 4400.0), ("73G", 6200.0), ("73H", 5400.0), ("73W", 6200.0), ("738", 5400.0), ("739", 5000.0),
+    ("744", 13400.0), ("747", 9800.0), ("74Y", 8200.0), ("752", 7200.0), ("753", 6300.0), ("757", 7200.0),
+    ("762", 9000.0), ("763", 11000.0), ("764", 10400.0), ("767", 11000.0), ("772", 9700.0), ("773", 11100.0),
+    ("77L", 15800.0), ("77W", 13600.0), ("777", 13600.0), ("787", 14000.0), ("788", 13600.0), ("789", 14000.0),
+    ("AT4", 1300.0), ("AT5", 1300.0), ("AT7", 1500.0), ("ATR", 1500.0), ("CR2", 3000.0), ("CR7", 3700.0),
+    ("CR9", 2900.0), ("CRJ", 3000.0), ("DH1", 1900.0), ("DH3", 1700.0), ("DH4", 2000.0), ("DH8", 2000.0),
+    ("E70", 3900.0), ("E75", 4000.0), ("E90", 4000.0), ("E95", 4200.0), ("ER4", 3000.0), ("ERD", 2800.0),
+    ("M88", 3800.0), ("SF3", 1700.0),
+];
+
+// Approximate maximum range of an aircraft type, if it is one of the common types
+pub fn typical_range_km(code: &str) -> Option<f64> {
+    TYPICAL_RANGES_KM.iter().find(|(known, _)| *known == code).map(|(_, range)| *range)
+}
+
+// IATA equipment codes for variants and families that airplanes.csv does not list, such as winglet (73H, 73W),
+// combi (74E) and freighter (77X) variants or generic family codes (CRJ, DH8, 32S)
+const EQUIPMENT_ALIASES: [&str; 51] = [
+    "313", "32A", "32B", "32C", "32S", "33X", "73C", "73H", "73J", "73M", "73N", "73Q", "73R",
+    "73W", "74E", "74H", "74M", "74N", "74Y", "75T", "75W", "76F", "76W", "77X", "AB4", "ARJ",
+    "BE1", "BE9", "BEC", "BET", "CN2", "CNA", "CNC", "CNT", "CRA", "CRJ", "D1C", "DC9", "DH8",
+    "EMJ", "ERJ", "IL9", "L4T", "M1F", "M80", "MA6", "NDE", "PA1", "SFB", "SWM", "YN7",
+];
+
+// Whether an equipment code names a real aircraft type: one of airplanes.csv, the range table or the aliases above
+pub fn is_known_equipment(aircraft: &HashMap<String, Aircraft>, code: &str) -> bool {
+    aircraft.contains_key(code) || typical_range_km(code).is_some() || EQUIPMENT_ALIASES.contains(&code)
+}
+
+// Aircraft types keyed by both their IATA and ICAO codes, as either may appear in routes.csv
+pub fn load_aircraft_from_csv(filename: &str, mode: LoadMode) -> Result<Loaded<HashMap<String, Aircraft>>, LoadError> {
+    let mut csv_reader = ReaderBuilder::new().flexible(true).from_path(filename).map_err(|error| LoadError::from_csv(filename, error))?;
+    let mut aircraft: HashMap<String, Aircraft> = HashMap::new();
+    let mut report = LoadReport::new(filename);
+
+    for result in csv_reader.records() {
+        let record = result.map_err(|error| LoadError::from_csv(filename, error))?;
+        let line = record.position().map(|position| position.line() as usize).unwrap_or(0);
+        report.rows += 1;
+        if record.len() < 4 {
+            let reason = format!("expected 4 fields, found {}", record.len());
+            report.reject(mode, LoadError::MalformedRow { line, reason }, &record.iter().collect::<Vec<_>>().join(","))?;
+            continue;
+        }
+
+        let code = |field: &str| if field == "\\N" { String::new() } else { field.to_string() };
+        let entry = Aircraft { name: record[1].to_string(), iata: code(&record[2]), icao: code(&record[3]) };
+        for key in [&entry.iata, &entry.icao] {
+            if !key.is_empty() {
+                aircraft.insert(key.clone(), entry.clone());
+            }
+        }
+        report.accepted += 1;
+    }
+
+    Ok(Loaded { data: aircraft, report })
+}
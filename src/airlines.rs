@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use csv::ReaderBuilder;
+use crate::load::{LoadError, LoadMode, LoadReport, Loaded};
+
+// One row of airlines.csv
+#[derive(Debug, Clone, PartialEq)]
+pub struct Airline {
+    pub name: String,
+    pub iata: String,
+    pub icao: String,
+    pub country: String,
+    pub active: bool,
+}
+
+// Airlines keyed by their OpenFlights airline ID, the "Airline ID" column of routes.csv
+pub fn load_airlines_from_csv(filename: &str, mode: LoadMode) -> Result<Loaded<HashMap<String, Airline>>, LoadError> {
+    let mut csv_reader = ReaderBuilder::new().flexible(true).from_path(filename).map_err(|error| LoadError::from_csv(filename, error))?;
+    let mut airlines: HashMap<String, Airline> = HashMap::new();
+    let mut report = LoadReport::new(filename);
+
+    for result in csv_reader.records() {
+        let record = result.map_err(|error| LoadError::from_csv(filename, error))?;
+        let line = record.position().map(|position| position.line() as usize).unwrap_or(0);
+        let text = record.iter().collect::<Vec<_>>().join(",");
+        report.rows += 1;
+        if record.len() < 9 {
+            let reason = format!("expected 9 fields, found {}", record.len());
+            report.reject(mode, LoadError::MalformedRow { line, reason }, &text)?;
+            continue;
+        }
+        let id = record[1].to_string();
+        if airlines.contains_key(&id) {
+            report.reject(mode, LoadError::DuplicateKey { line, key: id }, &text)?;
+            continue;
+        }
+
+        let code = |field: &str| if field == "\\N" || field == "-" { String::new() } else { field.to_string() };
+        airlines.insert(
+            id,
+            Airline {
+                name: record[2].to_string(),
+                iata: code(&record[4]),
+                icao: code(&record[5]),
+                country: code(&record[7]),
+                active: &record[8] == "Y",
+            },
+        );
+        report.accepted += 1;
+    }
+
+    Ok(Loaded { data: airlines, report })
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use csv::ReaderBuilder;
+use crate::airports::{Airport, AirportColumns};
+use crate::format::{detect_format, InputFormat};
+use crate::load::LoadError;
+
+// Elevations outside this range in feet are almost certainly typos; the lowest and highest
+// airports in service sit around -1,300 ft (Dead Sea) and 14,500 ft (Daocheng Yading)
+const MIN_ALTITUDE_FT: f64 = -1500.0;
+const MAX_ALTITUDE_FT: f64 = 15000.0;
+
+// Names of the checks, as accepted by --checks
+pub const CHECK_NAMES: [&str; 5] = ["coordinate-range", "null-island", "duplicate-code", "timezone", "altitude"];
+
+// One suspicious row of airports.csv
+#[derive(Debug, Clone, PartialEq)]
+pub struct AirportIssue {
+    pub line: usize,
+    // IATA code, or ICAO code for airports without one, as the airports are keyed when loaded
+    pub code: String,
+    pub check: &'static str,
+    pub detail: String,
+}
+
+// The columns of one airports.csv row the checks look at
+struct AirportRow {
+    line: usize,
+    iata: String,
+    icao: String,
+    fallback_code: String,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    altitude: Option<f64>,
+    // Standard UTC offset in hours and the tz database name, e.g. 10 and "Pacific/Port_Moresby"
+    offset: Option<f64>,
+    tz_name: String,
+}
+
+impl AirportRow {
+    fn code(&self) -> &str {
+        [&self.iata, &self.icao, &self.fallback_code].into_iter().find(|code| !code.is_empty()).unwrap_or(&self.iata)
+    }
+}
+
+// Rows of the airports file with the fields the loader would parse; rows too short to load are left to the loader's report
+fn read_airport_rows(filename: &str, format: InputFormat) -> Result<Vec<AirportRow>, LoadError> {
+    let columns = AirportColumns::of(format);
+    let mut csv_reader = ReaderBuilder::new()
+        .flexible(true)
+        .has_headers(format.has_header())
+        .from_path(filename)
+        .map_err(|error| LoadError::from_csv(filename, error))?;
+    let mut rows = Vec::new();
+    for result in csv_reader.records() {
+        let record = result.map_err(|error| LoadError::from_csv(filename, error))?;
+        if record.len() < columns.required() {
+            continue;
+        }
+        // OpenFlights writes \N for a missing value
+        let text = |index: Option<usize>| index.and_then(|index| record.get(index)).filter(|field| *field != "\\N").unwrap_or("").to_string();
+        let number = |index: Option<usize>| text(index).parse::<f64>().ok();
+        let iata = text(Some(columns.iata));
+        let icao = text(Some(columns.icao));
+        rows.push(AirportRow {
+            line: record.position().map(|position| position.line() as usize).unwrap_or(0),
+            fallback_code: text(columns.fallback_code),
+            iata,
+            icao,
+            latitude: number(Some(columns.latitude)),
+            longitude: number(Some(columns.longitude)),
+            altitude: number(Some(columns.altitude)),
+            offset: number(columns.offset),
+            tz_name: text(columns.tz_name),
+        });
+    }
+    Ok(rows)
+}
+
+// Run the selected checks over an airports file in any input format; issues are ordered by line
+pub fn check_airports(filename: &str, checks: &[&str]) -> Result<Vec<AirportIssue>, LoadError> {
+    check_airports_with_format(filename, detect_format(filename)?, checks)
+}
+
+pub fn check_airports_with_format(filename: &str, format: InputFormat, checks: &[&str]) -> Result<Vec<AirportIssue>, LoadError> {
+    let rows = read_airport_rows(filename, format)?;
+    let mut issues = Vec::new();
+    let mut report = |row: &AirportRow, check: &'static str, detail: String| {
+        if checks.contains(&check) {
+            issues.push(AirportIssue { line: row.line, code: row.code().to_string(), check, detail });
+        }
+    };
+
+    // The usual offset of each tz database name, in quarter hours, taken as the most common one among its airports
+    let mut offsets: HashMap<&str, HashMap<i64, usize>> = HashMap::new();
+    for row in &rows {
+        if let (Some(offset), false) = (row.offset, row.tz_name.is_empty()) {
+            *offsets.entry(row.tz_name.as_str()).or_default().entry(quarter_hours(offset)).or_default() += 1;
+        }
+    }
+    let usual_offsets: HashMap<&str, i64> = offsets
+        .into_iter()
+        .filter_map(|(name, counts)| counts.into_iter().max_by_key(|(offset, count)| (*count, -offset.abs())).map(|(offset, _)| (name, offset)))
+        .collect();
+
+    let mut first_lines: HashMap<(&str, &str), usize> = HashMap::new();
+    for row in &rows {
+        if let (Some(latitude), Some(longitude)) = (row.latitude, row.longitude) {
+            if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                report(row, "coordinate-range", format!("({}, {}) is off the globe", latitude, longitude));
+            } else if latitude == 0.0 && longitude == 0.0 {
+                report(row, "null-island", "placeholder coordinates (0, 0)".to_string());
+            }
+        }
+
+        for (kind, code) in [("IATA", &row.iata), ("ICAO", &row.icao)] {
+            if code.is_empty() {
+                continue;
+            }
+            match first_lines.get(&(kind, code.as_str())) {
+                Some(first) => report(row, "duplicate-code", format!("{} {} already used at line {}", kind, code, first)),
+                None => {
+                    first_lines.insert((kind, code.as_str()), row.line);
+                }
+            }
+        }
+
+        if let Some(offset) = row.offset {
+            if !(-12.0..=14.0).contains(&offset) {
+                report(row, "timezone", format!("UTC offset {} is out of range", offset));
+            } else if let Some(usual) = usual_offsets.get(row.tz_name.as_str()) {
+                if quarter_hours(offset) != *usual {
+                    report(row, "timezone", format!("UTC offset {} but {} is usually {}", offset, row.tz_name, *usual as f64 / 4.0));
+                }
+            }
+        }
+
+        if let Some(altitude) = row.altitude {
+            if !(MIN_ALTITUDE_FT..=MAX_ALTITUDE_FT).contains(&altitude) {
+                report(row, "altitude", format!("altitude {} ft", altitude));
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+fn quarter_hours(offset: f64) -> i64 {
+    (offset * 4.0).round() as i64
+}
+
+// Drop the airports read from flagged rows, e.g. before the route graph is built; returns how many were dropped.
+// Rows the loader already skipped as duplicates leave the airport loaded from the first row in place
+pub fn exclude_suspect_airports(airports: &mut HashMap<String, Airport>, issues: &[AirportIssue]) -> usize {
+    let before = airports.len();
+    for issue in issues {
+        if airports.get(&issue.code).is_some_and(|airport| airport.line == issue.line) {
+            airports.remove(&issue.code);
+        }
+    }
+    before - airports.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use crate::airports::load_airports_from_csv;
+    use crate::load::LoadMode;
+
+    #[test]
+    fn test_airport_sanity_checks() {
+        let path = env::temp_dir().join("test_airport_sanity_checks_airports.csv");
+        let rows = [
+            "index,Airport ID,Name,City,Country,IATA,ICAO,Latitude,Longitude,Altitude,Timezone,DST,Tz database time zone",
+            "0,1,Heathrow,London,United Kingdom,LHR,EGLL,51.4706,-0.461941,83,0,E,Europe/London",
+            "1,2,Gatwick,London,United Kingdom,LGW,EGKK,51.148102,-0.190278,202,0,E,Europe/London",
+            "2,3,Placeholder,Nowhere,Nowhere,NUL,\\N,0,0,0,0,E,Europe/London",
+            "3,4,Off the map,Nowhere,Nowhere,OFF,\\N,95,10,0,0,E,Europe/London",
+            "4,5,Gatwick again,London,United Kingdom,LGX,EGKK,51.148102,-0.190278,202,0,E,Europe/London",
+            "5,6,Wrong zone,London,United Kingdom,LCY,EGLC,51.505299,0.055278,19,3,E,Europe/London",
+            "6,7,Too high,London,United Kingdom,HIG,\\N,51.5,-0.1,52000,0,E,Europe/London",
+        ];
+        std::fs::write(&path, rows.join("\n")).unwrap();
+        let filename = path.to_str().unwrap();
+
+        let issues = check_airports(filename, &CHECK_NAMES).unwrap();
+        let found: Vec<(usize, &str, &str)> = issues.iter().map(|issue| (issue.line, issue.code.as_str(), issue.check)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, "NUL", "null-island"),
+                (5, "OFF", "coordinate-range"),
+                (6, "LGX", "duplicate-code"),
+                (7, "LCY", "timezone"),
+                (8, "HIG", "altitude"),
+            ]
+        );
+        assert!(issues[2].detail.contains("line 3"), "{}", issues[2].detail);
+        assert_eq!(check_airports(filename, &["altitude"]).unwrap().len(), 1);
+
+        let mut airports = load_airports_from_csv(filename, LoadMode::Lenient).unwrap().data;
+        assert_eq!(exclude_suspect_airports(&mut airports, &issues), 5);
+        let mut kept: Vec<&String> = airports.keys().collect();
+        kept.sort();
+        assert_eq!(kept, vec!["LGW", "LHR"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,40 +1,186 @@
 use std::collections::HashMap;
-use std::error::Error;
 use std::fs::File;
-use std::io::{BufReader, BufRead};
-use geoutils::{Distance, Location};
+use std::io::BufReader;
+use csv::{ReaderBuilder, StringRecord};
+use geoutils::Location;
+use crate::format::{detect_format, InputFormat};
+use crate::load::{LoadError, LoadMode, LoadReport, Loaded};
 
 #[derive(Debug, Clone)]
 pub struct Airport {
+    pub name: String,
+    pub city: String,
+    pub country: String,
+    // Four-letter ICAO code, empty when unknown
+    pub icao: String,
+    // Elevation in feet, as given in airports.csv
+    pub altitude: f64,
     pub location: Location,
+    // Line of airports.csv the airport was read from, 0 for airports built in code
+    pub line: usize,
+    // Routes departing or arriving here once routes are loaded, used to rank search results
+    pub routes: usize,
 }
 
 impl Airport {
-    pub fn new(latitude: f64, longitude: f64) -> Self {
+    pub fn new(name: &str, city: &str, country: &str, latitude: f64, longitude: f64) -> Self {
         let location = Location::new(latitude, longitude);
-        Airport { location }
+        Airport {
+            name: name.to_string(),
+            city: city.to_string(),
+            country: country.to_string(),
+            icao: String::new(),
+            altitude: 0.0,
+            location,
+            line: 0,
+            routes: 0,
+        }
+    }
+}
+
+// A csv row as it appeared in the file, for load reports
+fn row_text(record: &StringRecord) -> String {
+    record.iter().collect::<Vec<_>>().join(",")
+}
+
+// Column of each airport field in one of the input formats
+pub(crate) struct AirportColumns {
+    pub name: usize,
+    pub city: usize,
+    pub country: usize,
+    pub iata: usize,
+    pub icao: usize,
+    // Code used when the airport has neither IATA nor ICAO code, e.g. OurAirports' "ident"
+    pub fallback_code: Option<usize>,
+    pub latitude: usize,
+    pub longitude: usize,
+    pub altitude: usize,
+    // Standard UTC offset in hours and tz database name, which only OpenFlights provides
+    pub offset: Option<usize>,
+    pub tz_name: Option<usize>,
+}
+
+impl AirportColumns {
+    pub fn of(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Indexed => AirportColumns {
+                name: 2,
+                city: 3,
+                country: 4,
+                iata: 5,
+                icao: 6,
+                fallback_code: None,
+                latitude: 7,
+                longitude: 8,
+                altitude: 9,
+                offset: Some(10),
+                tz_name: Some(12),
+            },
+            // airports.dat is airports.csv without the index column
+            InputFormat::OpenFlights => AirportColumns {
+                name: 1,
+                city: 2,
+                country: 3,
+                iata: 4,
+                icao: 5,
+                fallback_code: None,
+                latitude: 6,
+                longitude: 7,
+                altitude: 8,
+                offset: Some(9),
+                tz_name: Some(11),
+            },
+            // Countries are ISO codes and the ICAO code is the "gps_code" column
+            InputFormat::OurAirports => AirportColumns {
+                name: 3,
+                city: 10,
+                country: 8,
+                iata: 13,
+                icao: 12,
+                fallback_code: Some(1),
+                latitude: 4,
+                longitude: 5,
+                altitude: 6,
+                offset: None,
+                tz_name: None,
+            },
+        }
+    }
+
+    // Fields a row needs before any of the required ones can be read
+    pub fn required(&self) -> usize {
+        [self.name, self.city, self.country, self.iata, self.icao, self.latitude, self.longitude].into_iter().max().unwrap_or(0) + 1
     }
 }
 
-pub fn load_airports_from_csv(filename: &str) -> Result<HashMap<String, Airport>, Box<dyn Error>> {
-    let file = File::open(filename)?;
+// Airports keyed by IATA code, or by ICAO code for airports without one, in whichever format the file is in
+pub fn load_airports_from_csv(filename: &str, mode: LoadMode) -> Result<Loaded<HashMap<String, Airport>>, LoadError> {
+    load_airports_with_format(filename, detect_format(filename)?, mode)
+}
+
+pub fn load_airports_with_format(filename: &str, format: InputFormat, mode: LoadMode) -> Result<Loaded<HashMap<String, Airport>>, LoadError> {
+    let file = File::open(filename).map_err(|source| LoadError::Io { path: filename.to_string(), source })?;
     let reader = BufReader::new(file);
     let mut airports: HashMap<String, Airport> = HashMap::new();
+    let mut report = LoadReport::new(filename);
+    let columns = AirportColumns::of(format);
+
+    // Names such as "Harstad/Narvik Airport, Evenes" are quoted, so let the csv reader split the fields
+    let mut csv_reader = ReaderBuilder::new().flexible(true).has_headers(format.has_header()).from_reader(reader);
+
+    for result in csv_reader.records() {
+        let record = result.map_err(|error| LoadError::from_csv(filename, error))?;
+        let line = record.position().map(|position| position.line() as usize).unwrap_or(0);
+        report.rows += 1;
+        if record.len() < columns.required() {
+            let reason = format!("expected at least {} fields, found {}", columns.required(), record.len());
+            report.reject(mode, LoadError::MalformedRow { line, reason }, &row_text(&record))?;
+            continue;
+        }
 
-    for line in reader.lines().skip(1) {
-        let record = line?;
-        let fields: Vec<_> = record.split(',').collect();
-        if fields.len() >= 9 {
-            if let (Ok(latitude), Ok(longitude)) = (fields[7].parse::<f64>(), fields[8].parse::<f64>()) {
-                let airport = Airport::new(latitude, longitude);
-                airports.insert(fields[5].to_string(), airport);
-            } else {
-                eprintln!("Skipping line with invalid latitude or longitude: {:?}", record);
+        let latitude = match record[columns.latitude].parse::<f64>() {
+            Ok(latitude) => latitude,
+            Err(_) => {
+                let value = record[columns.latitude].to_string();
+                report.reject(mode, LoadError::BadCoordinate { line, field: "latitude", value }, &row_text(&record))?;
+                continue;
+            }
+        };
+        let longitude = match record[columns.longitude].parse::<f64>() {
+            Ok(longitude) => longitude,
+            Err(_) => {
+                let value = record[columns.longitude].to_string();
+                report.reject(mode, LoadError::BadCoordinate { line, field: "longitude", value }, &row_text(&record))?;
+                continue;
             }
-        } else {
-            eprintln!("Skipping line with invalid format: {:?}", record);
+        };
+
+        // OpenFlights writes \N for a missing code, OurAirports leaves the field empty
+        let code = |index: usize| record.get(index).filter(|field| *field != "\\N").unwrap_or("").to_string();
+        let (iata, icao) = (code(columns.iata), code(columns.icao));
+        let key = [iata.clone(), icao.clone(), columns.fallback_code.map(code).unwrap_or_default()]
+            .into_iter()
+            .find(|key| !key.is_empty())
+            .unwrap_or_default();
+        if key.is_empty() {
+            report.reject(mode, LoadError::MalformedRow { line, reason: "no IATA or ICAO code".to_string() }, &row_text(&record))?;
+            continue;
         }
+        if airports.contains_key(&key) {
+            report.reject(mode, LoadError::DuplicateKey { line, key }, &row_text(&record))?;
+            continue;
+        }
+
+        let altitude = record.get(columns.altitude).and_then(|field| field.parse().ok()).unwrap_or(0.0);
+        let airport = Airport {
+            icao,
+            altitude,
+            line,
+            ..Airport::new(&record[columns.name], &record[columns.city], &record[columns.country], latitude, longitude)
+        };
+        airports.insert(key, airport);
+        report.accepted += 1;
     }
 
-    Ok(airports)
+    Ok(Loaded { data: airports, report })
 }
@@ -1,18 +1,21 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use crate::distance::Distance;
+use crate::graph::AdjacencyList;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct NodeWithDistanceAndPath {
-    pub distance: f64,
+    pub distance: Distance,
     pub path: Vec<String>,
 }
 
-pub fn bfs(adjacency_list: &HashMap<String, Vec<(String, f64)>>, source: &str) -> HashMap<String, NodeWithDistanceAndPath> {
+pub fn bfs(adjacency_list: &AdjacencyList, source: &str) -> HashMap<String, NodeWithDistanceAndPath> {
     let mut distances: HashMap<String, NodeWithDistanceAndPath> = HashMap::new();
     let mut visited: HashSet<String> = HashSet::new();
-    let mut queue: Vec<(String, f64, Vec<String>)> = Vec::new();
+    let mut queue: Vec<(String, Distance, Vec<String>)> = Vec::new();
 
     visited.insert(source.to_string());
-    queue.push((source.to_string(), 0.0, vec![source.to_string()]));
+    queue.push((source.to_string(), Distance::ZERO, vec![source.to_string()]));
 
     while let Some((node, dist, path)) = queue.pop() {
         // Insert the node's information into the distances HashMap
@@ -25,7 +28,7 @@ pub fn bfs(adjacency_list: &HashMap<String, Vec<(String, f64)>>, source: &str) -
                     visited.insert(neighbor.clone());
                     let mut new_path = path.clone();
                     new_path.push(neighbor.clone());
-                    queue.push((neighbor.clone(), dist + distance, new_path));
+                    queue.push((neighbor.clone(), dist + *distance, new_path));
                 }
             }
         }
@@ -34,9 +37,186 @@ pub fn bfs(adjacency_list: &HashMap<String, Vec<(String, f64)>>, source: &str) -
     // Mark unreachable nodes as infinite distance
     for node in adjacency_list.keys() {
         if !distances.contains_key(node) {
-            distances.insert(node.clone(), NodeWithDistanceAndPath { distance: std::f64::INFINITY, path: vec![] });
+            distances.insert(node.clone(), NodeWithDistanceAndPath { distance: Distance::INFINITY, path: vec![] });
         }
     }
 
     distances
 }
+
+// Number of flights needed to reach every airport reachable from the source
+pub fn hop_counts(adjacency_list: &AdjacencyList, source: &str) -> HashMap<String, usize> {
+    let mut hops: HashMap<String, usize> = HashMap::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+
+    hops.insert(source.to_string(), 0);
+    queue.push_back(source);
+
+    while let Some(node) = queue.pop_front() {
+        let next = hops[node] + 1;
+        if let Some(neighbors) = adjacency_list.get(node) {
+            for (neighbor, _) in neighbors {
+                if !hops.contains_key(neighbor) {
+                    hops.insert(neighbor.clone(), next);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    hops
+}
+
+// Min-heap entry for Dijkstra, ordered by distance
+#[derive(Debug, PartialEq)]
+struct QueueEntry {
+    distance: Distance,
+    node: String,
+}
+
+impl Eq for QueueEntry {}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance).then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Shortest distances from the source along with the previous airport on each shortest path
+pub fn shortest_path_tree(adjacency_list: &AdjacencyList, source: &str) -> (HashMap<String, Distance>, HashMap<String, String>) {
+    multi_source_shortest_path_tree(adjacency_list, &[(source.to_string(), Distance::ZERO)])
+}
+
+// Shortest distances when the trip may start at any of the sources, each with its own starting cost
+pub fn multi_source_shortest_path_tree(
+    adjacency_list: &AdjacencyList,
+    sources: &[(String, Distance)],
+) -> (HashMap<String, Distance>, HashMap<String, String>) {
+    let mut distances: HashMap<String, Distance> = HashMap::new();
+    let mut previous: HashMap<String, String> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    for (source, cost) in sources {
+        if distances.get(source).is_none_or(|&best| *cost < best) {
+            distances.insert(source.clone(), *cost);
+            heap.push(QueueEntry { distance: *cost, node: source.clone() });
+        }
+    }
+
+    while let Some(QueueEntry { distance, node }) = heap.pop() {
+        if distance > distances[&node] {
+            continue;
+        }
+        if let Some(neighbors) = adjacency_list.get(&node) {
+            for (neighbor, weight) in neighbors {
+                let candidate = distance + *weight;
+                if distances.get(neighbor).is_none_or(|&best| candidate < best) {
+                    distances.insert(neighbor.clone(), candidate);
+                    previous.insert(neighbor.clone(), node.clone());
+                    heap.push(QueueEntry { distance: candidate, node: neighbor.clone() });
+                }
+            }
+        }
+    }
+
+    (distances, previous)
+}
+
+// Walk the previous-airport links back from the target to rebuild the path
+pub fn reconstruct_path(previous: &HashMap<String, String>, source: &str, target: &str) -> Vec<String> {
+    let mut path = vec![target.to_string()];
+    let mut current = target;
+    while current != source {
+        match previous.get(current) {
+            Some(prev) => {
+                path.push(prev.clone());
+                current = prev;
+            }
+            None => return vec![],
+        }
+    }
+    path.reverse();
+    path
+}
+
+// Path back to whichever source the shortest path started from
+pub fn reconstruct_path_from_any(previous: &HashMap<String, String>, target: &str) -> Vec<String> {
+    let mut path = vec![target.to_string()];
+    let mut current = target;
+    while let Some(prev) = previous.get(current) {
+        path.push(prev.clone());
+        current = prev;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bfs() {
+        // Sample dataset for testing
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("A".to_string(), vec![("B".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("B".to_string(), vec![("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("C".to_string(), vec![("D".to_string(), Distance::from_km(1.0))]);
+
+        // Run BFS from node "A"
+        let distances = bfs(&adjacency_list, "A");
+
+        // Expected distances and paths
+        let expected_distances: HashMap<String, NodeWithDistanceAndPath> = [
+            ("A".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(0.0), path: vec!["A".to_string()] }),
+            ("B".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(1.0), path: vec!["A".to_string(), "B".to_string()] }),
+            ("C".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(2.0), path: vec!["A".to_string(), "B".to_string(), "C".to_string()] }),
+            ("D".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(3.0), path: vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()] }),
+        ].iter().cloned().collect();
+
+        // Check if the actual distances match the expected ones
+        for (node, expected_node) in expected_distances.iter() {
+            assert_eq!(distances.get(node), Some(expected_node));
+        }
+    }
+
+    #[test]
+    fn test_bfs_unreachable_airports() {
+        // Sample dataset for testing with unreachable airports
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("A".to_string(), vec![("B".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("B".to_string(), vec![("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("C".to_string(), vec![("D".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("E".to_string(), vec![("F".to_string(), Distance::from_km(1.0))]);
+
+        // Run BFS from node "A"
+        let distances = bfs(&adjacency_list, "A");
+
+        // Expected distances and paths
+        let expected_distances: HashMap<String, NodeWithDistanceAndPath> = [
+            ("A".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(0.0), path: vec!["A".to_string()] }),
+            ("B".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(1.0), path: vec!["A".to_string(), "B".to_string()] }),
+            ("C".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(2.0), path: vec!["A".to_string(), "B".to_string(), "C".to_string()] }),
+            ("D".to_string(), NodeWithDistanceAndPath { distance: Distance::from_km(3.0), path: vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()] }),
+        ].iter().cloned().collect();
+
+        // Check if the actual distances match the expected ones
+        for (node, expected_node) in expected_distances.iter() {
+            if let Some(actual_node) = distances.get(node) {
+                // Check distance
+                assert_eq!(actual_node.distance, expected_node.distance, "Distance mismatch for node {}", node);
+                // Check path
+                assert_eq!(actual_node.path, expected_node.path, "Path mismatch for node {}", node);
+            } else {
+                // Node not found in actual distances, assert that it's unreachable
+                assert!(!adjacency_list.contains_key(node), "Node {} should not be reachable", node);
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use rand::seq::SliceRandom;
+use std::error::Error;
+use main_code::distance::distance_with_fallback;
+use main_code::{load_airports_from_csv, DistanceModel, LoadMode};
+
+// Print the distance between 100 random pairs of airports
+fn main() -> Result<(), Box<dyn Error>> {
+    let airports = load_airports_from_csv("airports.csv", LoadMode::Lenient)?.data;
+
+    // Generate random pairs of airport IATA codes
+    let mut rng = rand::thread_rng();
+    let iata_codes: Vec<&String> = airports.keys().collect();
+    let random_pairs: Vec<(&String, &String)> = (0..100)
+        .map(|_| {
+            let iata1 = *iata_codes.choose(&mut rng).unwrap();
+            let iata2 = *iata_codes.choose(&mut rng).unwrap();
+            (iata1, iata2)
+        })
+        .collect();
+
+    // Calculate distances for random pairs of airports
+    for (iata1, iata2) in random_pairs {
+        let distance = distance_with_fallback(&airports[iata1].location, &airports[iata2].location, DistanceModel::Vincenty);
+        println!(
+            "Distance between Airport {} and Airport {} = {:.2} kilometers",
+            iata1, iata2, distance.km()
+        );
+    }
+
+    Ok(())
+}
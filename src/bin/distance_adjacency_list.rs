@@ -0,0 +1,22 @@
+use std::error::Error;
+use main_code::{adjacency_list_from_routes, load_airports_from_csv, load_routes_from_csv, DistanceModel, LoadMode};
+
+// Print the neighbors of the first 50 airports of the network built from valid_routes.csv
+fn main() -> Result<(), Box<dyn Error>> {
+    // Load location data from airports.csv
+    let airports = load_airports_from_csv("airports.csv", LoadMode::Lenient)?.data;
+
+    // Load adjacency list with connections from valid_routes.csv using location data
+    let routes = load_routes_from_csv("valid_routes.csv", LoadMode::Lenient)?.data;
+    let adjacency_list = adjacency_list_from_routes(&routes, &airports, DistanceModel::Vincenty, LoadMode::Lenient)?.data;
+
+    for (airport, neighbors) in adjacency_list.iter().take(50) {
+        print!("Airport {}: [", airport);
+        for (neighbor, distance) in neighbors {
+            println!("(\"{}\", {:.2} kilometers), ", neighbor, distance.km());
+        }
+        println!("]");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use std::error::Error;
+use main_code::validation::{read_route_records, validate_routes, write_validation_csv, UnknownAirports};
+use main_code::{load_airports_from_csv, InputFormat, LoadMode, RouteRule};
+
+// Copy the routes of routes.csv whose airports both have location data to valid_routes.csv;
+// `mainCode validate` runs the full set of rules
+fn main() -> Result<(), Box<dyn Error>> {
+    // Load location data from airports.csv
+    let airports = load_airports_from_csv("airports.csv", LoadMode::Lenient)?.data;
+
+    let (header, records) = read_route_records("routes.csv", InputFormat::Indexed)?;
+    let mut rules: Vec<Box<dyn RouteRule>> = vec![Box::new(UnknownAirports { airports: &airports })];
+    let result = validate_routes(header, records, &mut rules);
+    write_validation_csv(&result, "valid_routes.csv", "invalid_routes.csv")?;
+
+    println!("Total number of routes loaded: {}", result.clean.len());
+    println!("Total number of invalid routes: {}", result.rejected.len());
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use crate::airports::Airport;
+use crate::graph::{simple_graph, AdjacencyList};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Community {
+    pub airports: Vec<String>,
+    // Countries ranked by how many of the community's airports they hold
+    pub countries: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommunityDetection {
+    pub communities: Vec<Community>,
+    pub modularity: f64,
+}
+
+// Weighted graph over node indices; a self-loop holds twice the weight inside a merged community
+type WeightedGraph = Vec<HashMap<usize, f64>>;
+
+fn modularity(graph: &WeightedGraph, community: &[usize]) -> f64 {
+    let total: f64 = graph.iter().flat_map(|edges| edges.values()).sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    let mut inside: HashMap<usize, f64> = HashMap::new();
+    let mut degree: HashMap<usize, f64> = HashMap::new();
+    for (node, edges) in graph.iter().enumerate() {
+        for (&neighbor, &weight) in edges {
+            *degree.entry(community[node]).or_default() += weight;
+            if community[node] == community[neighbor] {
+                *inside.entry(community[node]).or_default() += weight;
+            }
+        }
+    }
+
+    degree
+        .iter()
+        .map(|(c, &tot)| inside.get(c).copied().unwrap_or(0.0) / total - (tot / total).powi(2))
+        .sum()
+}
+
+// Louvain local moving phase: move nodes to the neighboring community with the best modularity gain
+fn move_nodes(graph: &WeightedGraph) -> (Vec<usize>, bool) {
+    let degree: Vec<f64> = graph.iter().map(|edges| edges.values().sum()).collect();
+    let total: f64 = degree.iter().sum();
+    let mut community: Vec<usize> = (0..graph.len()).collect();
+    let mut community_degree = degree.clone();
+    let mut improved = false;
+
+    loop {
+        let mut moved = false;
+        for node in 0..graph.len() {
+            let current = community[node];
+            let mut links: HashMap<usize, f64> = HashMap::new();
+            for (&neighbor, &weight) in &graph[node] {
+                if neighbor != node {
+                    *links.entry(community[neighbor]).or_default() += weight;
+                }
+            }
+
+            community_degree[current] -= degree[node];
+            let gain = |c: usize| links.get(&c).copied().unwrap_or(0.0) - community_degree[c] * degree[node] / total;
+            let mut best = current;
+            let mut best_gain = gain(current);
+            let mut candidates: Vec<usize> = links.keys().copied().collect();
+            candidates.sort();
+            for c in candidates {
+                let candidate_gain = gain(c);
+                if candidate_gain > best_gain + 1e-12 {
+                    best = c;
+                    best_gain = candidate_gain;
+                }
+            }
+            community_degree[best] += degree[node];
+
+            if best != current {
+                community[node] = best;
+                moved = true;
+                improved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    (community, improved)
+}
+
+// Louvain aggregation phase: merge every community into a single node
+fn aggregate(graph: &WeightedGraph, community: &[usize]) -> (WeightedGraph, Vec<usize>) {
+    let mut renumber: HashMap<usize, usize> = HashMap::new();
+    let mut assignment = Vec::with_capacity(community.len());
+    for &c in community {
+        let next = renumber.len();
+        assignment.push(*renumber.entry(c).or_insert(next));
+    }
+
+    let mut merged: WeightedGraph = vec![HashMap::new(); renumber.len()];
+    for (node, edges) in graph.iter().enumerate() {
+        for (&neighbor, &weight) in edges {
+            *merged[assignment[node]].entry(assignment[neighbor]).or_default() += weight;
+        }
+    }
+
+    (merged, assignment)
+}
+
+// Group airports into regional communities with the Louvain method, one unit of weight per route
+pub fn detect_communities(adjacency_list: &AdjacencyList, airports: &HashMap<String, Airport>) -> CommunityDetection {
+    let simple = simple_graph(adjacency_list);
+    let mut codes: Vec<&String> = simple.keys().collect();
+    codes.sort();
+    let index: HashMap<&String, usize> = codes.iter().enumerate().map(|(i, &code)| (code, i)).collect();
+
+    let original: WeightedGraph = codes
+        .iter()
+        .map(|code| simple[*code].iter().map(|(neighbor, _)| (index[neighbor], 1.0)).collect())
+        .collect();
+
+    // Community of every original airport, refined level by level
+    let mut membership: Vec<usize> = (0..codes.len()).collect();
+    let mut graph = original.clone();
+    loop {
+        let (community, improved) = move_nodes(&graph);
+        if !improved {
+            break;
+        }
+        let (merged, assignment) = aggregate(&graph, &community);
+        for member in membership.iter_mut() {
+            *member = assignment[*member];
+        }
+        graph = merged;
+    }
+
+    let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+    for (i, code) in codes.iter().enumerate() {
+        groups.entry(membership[i]).or_default().push((*code).clone());
+    }
+
+    let mut communities: Vec<Community> = groups
+        .into_values()
+        .map(|members| {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for code in &members {
+                if let Some(airport) = airports.get(code) {
+                    *counts.entry(airport.country.clone()).or_default() += 1;
+                }
+            }
+            let mut countries: Vec<(String, usize)> = counts.into_iter().collect();
+            countries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            Community { airports: members, countries }
+        })
+        .collect();
+    communities.sort_by(|a, b| b.airports.len().cmp(&a.airports.len()).then_with(|| a.airports.cmp(&b.airports)));
+
+    CommunityDetection { communities, modularity: modularity(&original, &membership) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_detect_communities() {
+        // Two triangles joined by a single route C - D
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        let routes = [("A", "B"), ("B", "C"), ("A", "C"), ("D", "E"), ("E", "F"), ("D", "F"), ("C", "D")];
+        for (from, to) in routes {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(1.0)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(1.0)));
+        }
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        for code in ["A", "B", "C"] {
+            airports.insert(code.to_string(), Airport::new(code, code, "Indonesia", 0.0, 0.0));
+        }
+        for code in ["D", "E", "F"] {
+            airports.insert(code.to_string(), Airport::new(code, code, "Jamaica", 0.0, 0.0));
+        }
+
+        let detection = detect_communities(&adjacency_list, &airports);
+
+        assert_eq!(detection.communities.len(), 2);
+        assert_eq!(detection.communities[0].airports, vec!["A", "B", "C"]);
+        assert_eq!(detection.communities[0].countries, vec![("Indonesia".to_string(), 3)]);
+        assert_eq!(detection.communities[1].airports, vec!["D", "E", "F"]);
+        assert!((detection.modularity - 5.0 / 14.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet};
+use crate::graph::{simple_graph, AdjacencyList};
+
+// An airport whose closure disconnects the listed airports from the rest of its component
+#[derive(Debug, Clone, PartialEq)]
+pub struct CriticalAirport {
+    pub airport: String,
+    pub cut_off: Vec<String>,
+}
+
+// A route whose closure disconnects the listed airports from the rest of their component
+#[derive(Debug, Clone, PartialEq)]
+pub struct CriticalRoute {
+    pub from: String,
+    pub to: String,
+    pub cut_off: Vec<String>,
+}
+
+// Airports reachable from start without passing through the blocked airport or the blocked route
+fn reachable(
+    adjacency_list: &AdjacencyList,
+    start: &str,
+    blocked_airport: Option<&str>,
+    blocked_route: Option<(&str, &str)>,
+) -> HashSet<String> {
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut stack = vec![start.to_string()];
+    visited.insert(start.to_string());
+
+    while let Some(node) = stack.pop() {
+        for (neighbor, _) in &adjacency_list[&node] {
+            if Some(neighbor.as_str()) == blocked_airport {
+                continue;
+            }
+            if let Some((a, b)) = blocked_route {
+                if (node == a && neighbor == b) || (node == b && neighbor == a) {
+                    continue;
+                }
+            }
+            if visited.insert(neighbor.clone()) {
+                stack.push(neighbor.clone());
+            }
+        }
+    }
+
+    visited
+}
+
+// Depth-first search (Tarjan) returning the articulation points and bridges of an undirected graph
+fn tarjan(adjacency_list: &AdjacencyList) -> (Vec<String>, Vec<(String, String)>) {
+    let mut airports: Vec<&String> = adjacency_list.keys().collect();
+    airports.sort();
+
+    let mut discovery: HashMap<&String, usize> = HashMap::new();
+    let mut low: HashMap<&String, usize> = HashMap::new();
+    let mut articulation: HashSet<&String> = HashSet::new();
+    let mut bridges: Vec<(String, String)> = Vec::new();
+    let mut time = 0;
+
+    for &root in &airports {
+        if discovery.contains_key(root) {
+            continue;
+        }
+        discovery.insert(root, time);
+        low.insert(root, time);
+        time += 1;
+
+        let mut root_children = 0;
+        // (airport, parent, index of the next neighbor to visit)
+        let mut stack: Vec<(&String, Option<&String>, usize)> = vec![(root, None, 0)];
+
+        while let Some((node, parent, index)) = stack.pop() {
+            let neighbors = &adjacency_list[node];
+            if index < neighbors.len() {
+                stack.push((node, parent, index + 1));
+                let neighbor = &neighbors[index].0;
+                if Some(neighbor) == parent {
+                    continue;
+                }
+                if let Some(&seen) = discovery.get(neighbor) {
+                    let node_low = low[node].min(seen);
+                    low.insert(node, node_low);
+                } else {
+                    discovery.insert(neighbor, time);
+                    low.insert(neighbor, time);
+                    time += 1;
+                    if node == root {
+                        root_children += 1;
+                    }
+                    stack.push((neighbor, Some(node), 0));
+                }
+            } else if let Some(parent) = parent {
+                // Finished this airport, propagate its low value back to the parent
+                let parent_low = low[parent].min(low[node]);
+                low.insert(parent, parent_low);
+                if parent != root && low[node] >= discovery[parent] {
+                    articulation.insert(parent);
+                }
+                if low[node] > discovery[parent] {
+                    bridges.push((parent.clone(), node.clone()));
+                }
+            }
+        }
+
+        if root_children > 1 {
+            articulation.insert(root);
+        }
+    }
+
+    let mut articulation: Vec<String> = articulation.into_iter().cloned().collect();
+    articulation.sort();
+    (articulation, bridges)
+}
+
+// Airports whose closure disconnects part of the network, with the airports that would be cut off
+pub fn articulation_points(adjacency_list: &AdjacencyList) -> Vec<CriticalAirport> {
+    let simple = simple_graph(adjacency_list);
+    let (articulation, _) = tarjan(&simple);
+
+    let mut critical: Vec<CriticalAirport> = articulation
+        .into_iter()
+        .map(|airport| {
+            // Split the remaining neighbors into pieces and keep everything outside the largest one
+            let mut pieces: Vec<HashSet<String>> = Vec::new();
+            for (neighbor, _) in &simple[&airport] {
+                if !pieces.iter().any(|piece| piece.contains(neighbor)) {
+                    pieces.push(reachable(&simple, neighbor, Some(&airport), None));
+                }
+            }
+            pieces.sort_by_key(|piece| std::cmp::Reverse(piece.len()));
+            let mut cut_off: Vec<String> = pieces.into_iter().skip(1).flatten().collect();
+            cut_off.sort();
+            CriticalAirport { airport, cut_off }
+        })
+        .collect();
+
+    // Closures that strand the most airports first
+    critical.sort_by_key(|c| std::cmp::Reverse(c.cut_off.len()));
+    critical
+}
+
+// Routes whose closure disconnects part of the network, with the airports on the smaller side
+pub fn bridges(adjacency_list: &AdjacencyList) -> Vec<CriticalRoute> {
+    let simple = simple_graph(adjacency_list);
+    let (_, bridges) = tarjan(&simple);
+
+    let mut critical: Vec<CriticalRoute> = bridges
+        .into_iter()
+        .map(|(from, to)| {
+            let to_side = reachable(&simple, &to, None, Some((&from, &to)));
+            let from_side = reachable(&simple, &from, None, Some((&from, &to)));
+            let smaller = if to_side.len() <= from_side.len() { to_side } else { from_side };
+            let mut cut_off: Vec<String> = smaller.into_iter().collect();
+            cut_off.sort();
+            CriticalRoute { from, to, cut_off }
+        })
+        .collect();
+
+    critical.sort_by_key(|c| std::cmp::Reverse(c.cut_off.len()));
+    critical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_articulation_points_and_bridges() {
+        // Triangle A - B - C with a spur C - D - E
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("A".to_string(), vec![("B".to_string(), Distance::from_km(1.0)), ("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("B".to_string(), vec![("A".to_string(), Distance::from_km(1.0)), ("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("C".to_string(), vec![("A".to_string(), Distance::from_km(1.0)), ("B".to_string(), Distance::from_km(1.0)), ("D".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("D".to_string(), vec![("C".to_string(), Distance::from_km(1.0)), ("E".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("E".to_string(), vec![("D".to_string(), Distance::from_km(1.0))]);
+
+        let critical_airports = articulation_points(&adjacency_list);
+        assert_eq!(critical_airports.len(), 2);
+        assert_eq!(critical_airports[0].airport, "C");
+        assert_eq!(critical_airports[0].cut_off, vec!["D", "E"]);
+        assert_eq!(critical_airports[1].airport, "D");
+        assert_eq!(critical_airports[1].cut_off, vec!["E"]);
+
+        let critical_routes = bridges(&adjacency_list);
+        assert_eq!(critical_routes.len(), 2);
+        assert_eq!(critical_routes[0].cut_off, vec!["D", "E"]);
+        assert_eq!(critical_routes[1].cut_off, vec!["E"]);
+    }
+}
@@ -0,0 +1,243 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+use std::str::FromStr;
+use geoutils::Location;
+
+// Mean Earth radius in kilometers
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+const KM_PER_STATUTE_MILE: f64 = 1.609344;
+const KM_PER_NAUTICAL_MILE: f64 = 1.852;
+
+// A length along the ground or through the air, stored in kilometers
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Distance(f64);
+
+impl Distance {
+    pub const ZERO: Distance = Distance(0.0);
+    pub const INFINITY: Distance = Distance(f64::INFINITY);
+
+    pub fn from_km(km: f64) -> Self {
+        Distance(km)
+    }
+
+    pub fn from_meters(meters: f64) -> Self {
+        Distance(meters / 1000.0)
+    }
+
+    pub fn km(self) -> f64 {
+        self.0
+    }
+
+    pub fn in_units(self, units: DistanceUnit) -> f64 {
+        match units {
+            DistanceUnit::Kilometers => self.0,
+            DistanceUnit::StatuteMiles => self.0 / KM_PER_STATUTE_MILE,
+            DistanceUnit::NauticalMiles => self.0 / KM_PER_NAUTICAL_MILE,
+        }
+    }
+
+    pub fn is_finite(self) -> bool {
+        self.0.is_finite()
+    }
+
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Add for Distance {
+    type Output = Distance;
+
+    fn add(self, other: Distance) -> Distance {
+        Distance(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Distance {
+    fn add_assign(&mut self, other: Distance) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Distance {
+    type Output = Distance;
+
+    fn sub(self, other: Distance) -> Distance {
+        Distance(self.0 - other.0)
+    }
+}
+
+impl Sum for Distance {
+    fn sum<I: Iterator<Item = Distance>>(iter: I) -> Distance {
+        iter.fold(Distance::ZERO, Add::add)
+    }
+}
+
+// Unit distances are reported in, chosen with --units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceUnit {
+    Kilometers,
+    StatuteMiles,
+    NauticalMiles,
+}
+
+impl DistanceUnit {
+    // Short name used in CSV column headers, e.g. distance_km
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            DistanceUnit::Kilometers => "km",
+            DistanceUnit::StatuteMiles => "mi",
+            DistanceUnit::NauticalMiles => "nmi",
+        }
+    }
+
+    // A distance with two decimals and the unit spelled out, e.g. "5554.23 kilometers"
+    pub fn format(&self, distance: Distance) -> String {
+        let name = match self {
+            DistanceUnit::Kilometers => "kilometers",
+            DistanceUnit::StatuteMiles => "miles",
+            DistanceUnit::NauticalMiles => "nautical miles",
+        };
+        format!("{:.2} {}", distance.in_units(*self), name)
+    }
+}
+
+impl FromStr for DistanceUnit {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "km" => Ok(DistanceUnit::Kilometers),
+            "mi" => Ok(DistanceUnit::StatuteMiles),
+            "nmi" => Ok(DistanceUnit::NauticalMiles),
+            other => Err(format!("Unknown distance unit: {} (expected km, mi or nmi)", other)),
+        }
+    }
+}
+
+// Formula used to compute the distance between two airports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceModel {
+    // Great circle on a sphere; always converges
+    Haversine,
+    // Ellipsoidal (WGS-84) via geoutils; most accurate but iterative and may fail near antipodes
+    Vincenty,
+    // Great circle on a sphere using the law of cosines
+    SphericalLawOfCosines,
+}
+
+impl FromStr for DistanceModel {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "haversine" => Ok(DistanceModel::Haversine),
+            "vincenty" => Ok(DistanceModel::Vincenty),
+            "cosines" => Ok(DistanceModel::SphericalLawOfCosines),
+            other => Err(format!("Unknown distance model: {} (expected haversine, vincenty or cosines)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DistanceError {
+    // Vincenty's iteration did not settle, typically for nearly antipodal points
+    NoConvergence { from: (f64, f64), to: (f64, f64) },
+}
+
+impl fmt::Display for DistanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DistanceError::NoConvergence { from, to } => write!(
+                f,
+                "Vincenty's formula failed to converge between ({}, {}) and ({}, {})",
+                from.0, from.1, to.0, to.1
+            ),
+        }
+    }
+}
+
+impl Error for DistanceError {}
+
+// Great-circle distance in kilometers on a spherical Earth
+pub fn haversine_km(from: &Location, to: &Location) -> f64 {
+    let (lat1, lat2) = (from.latitude().to_radians(), to.latitude().to_radians());
+    let delta_lat = lat2 - lat1;
+    let delta_lon = (to.longitude() - from.longitude()).to_radians();
+    let h = (delta_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+fn law_of_cosines_km(from: &Location, to: &Location) -> f64 {
+    let (lat1, lat2) = (from.latitude().to_radians(), to.latitude().to_radians());
+    let delta_lon = (to.longitude() - from.longitude()).to_radians();
+    // Rounding can push the cosine just outside [-1, 1] for coincident or antipodal points
+    let cosine = (lat1.sin() * lat2.sin() + lat1.cos() * lat2.cos() * delta_lon.cos()).clamp(-1.0, 1.0);
+    EARTH_RADIUS_KM * cosine.acos()
+}
+
+// Distance with the chosen model, or an error if Vincenty does not converge
+pub fn distance_between(from: &Location, to: &Location, model: DistanceModel) -> Result<Distance, DistanceError> {
+    match model {
+        DistanceModel::Haversine => Ok(Distance::from_km(haversine_km(from, to))),
+        DistanceModel::SphericalLawOfCosines => Ok(Distance::from_km(law_of_cosines_km(from, to))),
+        DistanceModel::Vincenty => match from.distance_to(to) {
+            Ok(distance) if distance.meters().is_finite() => Ok(Distance::from_meters(distance.meters())),
+            _ => Err(DistanceError::NoConvergence {
+                from: (from.latitude(), from.longitude()),
+                to: (to.latitude(), to.longitude()),
+            }),
+        },
+    }
+}
+
+// Distance with the chosen model, falling back to haversine when Vincenty fails
+pub fn distance_with_fallback(from: &Location, to: &Location, model: DistanceModel) -> Distance {
+    distance_between(from, to, model).unwrap_or_else(|_| Distance::from_km(haversine_km(from, to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_models_near_antipodes() {
+        let madrid = geoutils::Location::new(40.4168, -3.7038);
+        let wellington_ish = geoutils::Location::new(-40.4168, 176.2962);
+
+        // Vincenty gives up on nearly antipodal points instead of panicking
+        assert!(matches!(
+            distance_between(&madrid, &wellington_ish, DistanceModel::Vincenty),
+            Err(DistanceError::NoConvergence { .. })
+        ));
+        let fallback = distance_with_fallback(&madrid, &wellington_ish, DistanceModel::Vincenty);
+        assert!((fallback.km() - std::f64::consts::PI * EARTH_RADIUS_KM).abs() < 1.0);
+
+        let jfk = geoutils::Location::new(40.639801, -73.7789);
+        let lhr = geoutils::Location::new(51.4706, -0.461941);
+        let haversine = distance_between(&jfk, &lhr, DistanceModel::Haversine).unwrap();
+        let cosines = distance_between(&jfk, &lhr, DistanceModel::SphericalLawOfCosines).unwrap();
+        let vincenty = distance_between(&jfk, &lhr, DistanceModel::Vincenty).unwrap();
+        assert!((haversine.km() - cosines.km()).abs() < 1e-6);
+        assert!((haversine.km() - vincenty.km()).abs() < 20.0);
+        assert_eq!("cosines".parse::<DistanceModel>(), Ok(DistanceModel::SphericalLawOfCosines));
+    }
+
+    #[test]
+    fn test_distance_units() {
+        let distance = Distance::from_km(1852.0);
+        assert_eq!(distance.in_units(DistanceUnit::NauticalMiles), 1000.0);
+        assert!((distance.in_units(DistanceUnit::StatuteMiles) - 1150.78).abs() < 0.01);
+        assert_eq!(DistanceUnit::Kilometers.format(distance), "1852.00 kilometers");
+        assert_eq!("nmi".parse::<DistanceUnit>(), Ok(DistanceUnit::NauticalMiles));
+        assert!("furlongs".parse::<DistanceUnit>().is_err());
+
+        let legs = [Distance::from_km(1.5), Distance::from_km(2.5)];
+        assert_eq!(legs.iter().copied().sum::<Distance>(), Distance::from_km(4.0));
+        assert!(Distance::ZERO < Distance::INFINITY);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use crate::airports::Airport;
+use crate::graph::{simple_graph, AdjacencyList, Route};
+
+// One undirected route of the exported graph with every airline flying it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportEdge {
+    pub from: String,
+    pub to: String,
+    pub distance_km: f64,
+    pub airlines: Vec<String>,
+}
+
+// The graph to export, optionally restricted to a set of airports
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportGraph {
+    pub airports: Vec<String>,
+    pub degrees: HashMap<String, usize>,
+    pub edges: Vec<ExportEdge>,
+}
+
+pub fn export_graph(adjacency_list: &AdjacencyList, routes: &[Route], filter: Option<&HashSet<String>>) -> ExportGraph {
+    let simple = simple_graph(adjacency_list);
+    let keep = |code: &String| filter.is_none_or(|set| set.contains(code));
+
+    let mut airlines: HashMap<(&String, &String), BTreeSet<&String>> = HashMap::new();
+    for route in routes {
+        let pair = if route.from <= route.to { (&route.from, &route.to) } else { (&route.to, &route.from) };
+        airlines.entry(pair).or_default().insert(&route.airline);
+    }
+
+    let mut edges: Vec<ExportEdge> = Vec::new();
+    let mut nodes: BTreeSet<String> = BTreeSet::new();
+    for (from, neighbors) in &simple {
+        if !keep(from) {
+            continue;
+        }
+        nodes.insert(from.clone());
+        for (to, distance) in neighbors {
+            if from < to && keep(to) {
+                edges.push(ExportEdge {
+                    from: from.clone(),
+                    to: to.clone(),
+                    distance_km: distance.km(),
+                    airlines: airlines.get(&(from, to)).map(|set| set.iter().map(|a| (*a).clone()).collect()).unwrap_or_default(),
+                });
+            }
+        }
+    }
+    edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+
+    let degrees = simple.iter().map(|(code, neighbors)| (code.clone(), neighbors.len())).collect();
+    ExportGraph { airports: nodes.into_iter().collect(), degrees, edges }
+}
+
+fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Node attributes shared by both formats: name, country, lat, lon, degree
+fn node_attributes(code: &str, graph: &ExportGraph, airports: &HashMap<String, Airport>) -> BTreeMap<&'static str, String> {
+    let mut attributes = BTreeMap::new();
+    if let Some(airport) = airports.get(code) {
+        attributes.insert("name", airport.name.clone());
+        attributes.insert("country", airport.country.clone());
+        attributes.insert("lat", format!("{:.6}", airport.location.latitude()));
+        attributes.insert("lon", format!("{:.6}", airport.location.longitude()));
+    }
+    attributes.insert("degree", graph.degrees.get(code).copied().unwrap_or(0).to_string());
+    attributes
+}
+
+pub fn to_dot(graph: &ExportGraph, airports: &HashMap<String, Airport>) -> String {
+    let mut dot = String::from("graph routes {\n");
+    for code in &graph.airports {
+        let attributes: Vec<String> = node_attributes(code, graph, airports)
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, dot_string(&value)))
+            .collect();
+        dot.push_str(&format!("  {} [{}];\n", dot_string(code), attributes.join(", ")));
+    }
+    for edge in &graph.edges {
+        dot.push_str(&format!(
+            "  {} -- {} [distance_km={:.2}, airlines={}];\n",
+            dot_string(&edge.from),
+            dot_string(&edge.to),
+            edge.distance_km,
+            dot_string(&edge.airlines.join(" ")),
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn to_graphml(graph: &ExportGraph, airports: &HashMap<String, Airport>) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    let keys = [
+        ("name", "node", "string"),
+        ("country", "node", "string"),
+        ("lat", "node", "double"),
+        ("lon", "node", "double"),
+        ("degree", "node", "int"),
+        ("distance_km", "edge", "double"),
+        ("airlines", "edge", "string"),
+    ];
+    for (name, target, kind) in keys {
+        xml.push_str(&format!("  <key id=\"{0}\" for=\"{1}\" attr.name=\"{0}\" attr.type=\"{2}\"/>\n", name, target, kind));
+    }
+
+    xml.push_str("  <graph id=\"routes\" edgedefault=\"undirected\">\n");
+    for code in &graph.airports {
+        xml.push_str(&format!("    <node id=\"{}\">\n", xml_escape(code)));
+        for (key, value) in node_attributes(code, graph, airports) {
+            xml.push_str(&format!("      <data key=\"{}\">{}</data>\n", key, xml_escape(&value)));
+        }
+        xml.push_str("    </node>\n");
+    }
+    for edge in &graph.edges {
+        xml.push_str(&format!("    <edge source=\"{}\" target=\"{}\">\n", xml_escape(&edge.from), xml_escape(&edge.to)));
+        xml.push_str(&format!("      <data key=\"distance_km\">{:.2}</data>\n", edge.distance_km));
+        xml.push_str(&format!("      <data key=\"airlines\">{}</data>\n", xml_escape(&edge.airlines.join(" "))));
+        xml.push_str("    </edge>\n");
+    }
+    xml.push_str("  </graph>\n</graphml>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::DistanceModel;
+    use crate::graph::{adjacency_list_from_routes, Route};
+    use crate::load::LoadMode;
+
+    #[test]
+    fn test_graph_export() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("ZMG".to_string(), Airport::new("Magdeburg \"City\" Airport", "Magdeburg", "Germany", 52.073612, 11.626389));
+        airports.insert("FRA".to_string(), Airport::new("Frankfurt am Main Airport", "Frankfurt", "Germany", 50.033333, 8.570556));
+        airports.insert("JFK".to_string(), Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789));
+
+        let routes = vec![
+            Route { airline: "LH".to_string(), from: "ZMG".to_string(), to: "FRA".to_string(), line: 1 },
+            Route { airline: "LH".to_string(), from: "FRA".to_string(), to: "ZMG".to_string(), line: 2 },
+            Route { airline: "UA".to_string(), from: "FRA".to_string(), to: "JFK".to_string(), line: 3 },
+            Route { airline: "LH".to_string(), from: "JFK".to_string(), to: "FRA".to_string(), line: 4 },
+        ];
+        let adjacency_list = adjacency_list_from_routes(&routes, &airports, DistanceModel::Vincenty, LoadMode::Strict).unwrap().data;
+
+        let graph = export_graph(&adjacency_list, &routes, None);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].from, "FRA");
+        assert_eq!(graph.edges[0].to, "JFK");
+        assert_eq!(graph.edges[0].airlines, vec!["LH", "UA"]);
+
+        let dot = to_dot(&graph, &airports);
+        assert!(dot.contains("\"ZMG\" [country=\"Germany\", degree=\"1\", lat="));
+        assert!(dot.contains("name=\"Magdeburg \\\"City\\\" Airport\""));
+
+        let germany: HashSet<String> = ["FRA".to_string(), "ZMG".to_string()].into_iter().collect();
+        let graphml = to_graphml(&export_graph(&adjacency_list, &routes, Some(&germany)), &airports);
+        assert_eq!(graphml.matches("<node ").count(), 2);
+        assert_eq!(graphml.matches("<edge ").count(), 1);
+        assert!(graphml.contains("Magdeburg &quot;City&quot; Airport"));
+    }
+}
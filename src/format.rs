@@ -0,0 +1,115 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+use crate::load::LoadError;
+
+// Layout of an airports or routes file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputFormat {
+    // This repo's CSVs: a header row and a leading "index" column
+    Indexed,
+    // The original headerless OpenFlights airports.dat and routes.dat
+    OpenFlights,
+    // OurAirports airports.csv export, with "id","ident","type","name",... columns; it has no routes
+    OurAirports,
+}
+
+impl InputFormat {
+    pub fn has_header(self) -> bool {
+        self != InputFormat::OpenFlights
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "indexed" => Ok(InputFormat::Indexed),
+            "openflights" => Ok(InputFormat::OpenFlights),
+            "ourairports" => Ok(InputFormat::OurAirports),
+            other => Err(format!("Unknown input format: {} (expected indexed, openflights or ourairports)", other)),
+        }
+    }
+}
+
+// Guess the format from the first line: this repo's header starts with "index", OurAirports names its
+// columns "ident", "latitude_deg" and so on, and anything else is taken to be a headerless OpenFlights file
+pub fn detect_format(filename: &str) -> Result<InputFormat, LoadError> {
+    let file = File::open(filename).map_err(|source| LoadError::Io { path: filename.to_string(), source })?;
+    let mut first_line = String::new();
+    BufReader::new(file).read_line(&mut first_line).map_err(|source| LoadError::Io { path: filename.to_string(), source })?;
+
+    let columns: Vec<String> = first_line.trim_start_matches('\u{feff}').split(',').map(|column| column.trim().trim_matches('"').to_string()).collect();
+    if columns.first().is_some_and(|column| column == "index") {
+        Ok(InputFormat::Indexed)
+    } else if columns.iter().any(|column| column == "ident") && columns.iter().any(|column| column == "latitude_deg") {
+        Ok(InputFormat::OurAirports)
+    } else {
+        Ok(InputFormat::OpenFlights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use crate::airports::load_airports_from_csv;
+    use crate::distance::DistanceModel;
+    use crate::graph::{adjacency_list_from_routes, load_routes_from_csv, load_routes_with_format, Route};
+    use crate::load::LoadMode;
+
+    #[test]
+    fn test_input_formats() {
+        let dir = env::temp_dir();
+        let openflights_airports = dir.join("test_input_formats_airports.dat");
+        std::fs::write(
+            &openflights_airports,
+            "507,\"London Heathrow Airport\",\"London\",\"United Kingdom\",\"LHR\",\"EGLL\",51.4706,-0.461941,83,0,\"E\",\"Europe/London\",\"airport\",\"OurAirports\"\n\
+             1382,\"Charles de Gaulle International Airport\",\"Paris\",\"France\",\"CDG\",\"LFPG\",49.012798,2.55,392,1,\"E\",\"Europe/Paris\",\"airport\",\"OurAirports\"\n",
+        )
+        .unwrap();
+        let ourairports = dir.join("test_input_formats_ourairports.csv");
+        std::fs::write(
+            &ourairports,
+            "\"id\",\"ident\",\"type\",\"name\",\"latitude_deg\",\"longitude_deg\",\"elevation_ft\",\"continent\",\"iso_country\",\"iso_region\",\"municipality\",\"scheduled_service\",\"gps_code\",\"iata_code\",\"local_code\"\n\
+             2434,\"EGLL\",\"large_airport\",\"London Heathrow Airport\",51.4706,-0.461941,83,\"EU\",\"GB\",\"GB-ENG\",\"London\",\"yes\",\"EGLL\",\"LHR\",\n\
+             6523,\"00A\",\"heliport\",\"Total RF Heliport\",40.070985,-74.933689,11,\"NA\",\"US\",\"US-PA\",\"Bensalem\",\"no\",\"\",\"\",\"00A\"\n",
+        )
+        .unwrap();
+        let openflights_routes = dir.join("test_input_formats_routes.dat");
+        std::fs::write(&openflights_routes, "AF,137,CDG,1382,LHR,507,,0,320\nBA,1355,LHR,507,CDG,1382,,0,319\n").unwrap();
+        let (airports_file, ourairports_file, routes_file) =
+            (openflights_airports.to_str().unwrap(), ourairports.to_str().unwrap(), openflights_routes.to_str().unwrap());
+
+        assert_eq!(detect_format("airports.csv").unwrap(), InputFormat::Indexed);
+        assert_eq!(detect_format(airports_file).unwrap(), InputFormat::OpenFlights);
+        assert_eq!(detect_format(ourairports_file).unwrap(), InputFormat::OurAirports);
+        assert_eq!(detect_format(routes_file).unwrap(), InputFormat::OpenFlights);
+        assert!("csv".parse::<InputFormat>().is_err());
+
+        let loaded = load_airports_from_csv(airports_file, LoadMode::Strict).unwrap();
+        assert_eq!((loaded.report.rows, loaded.report.accepted), (2, 2));
+        assert_eq!((loaded.data["LHR"].icao.as_str(), loaded.data["LHR"].line, loaded.data["CDG"].altitude), ("EGLL", 1, 392.0));
+        let airports = loaded.data;
+
+        // Airports without IATA or ICAO code are keyed by their OurAirports ident
+        let loaded = load_airports_from_csv(ourairports_file, LoadMode::Strict).unwrap();
+        let mut codes: Vec<&String> = loaded.data.keys().collect();
+        codes.sort();
+        assert_eq!(codes, vec!["00A", "LHR"]);
+        assert_eq!((loaded.data["LHR"].country.as_str(), loaded.data["LHR"].line), ("GB", 2));
+
+        let routes = load_routes_from_csv(routes_file, LoadMode::Strict).unwrap().data;
+        assert_eq!(routes[0], Route { airline: "AF".to_string(), from: "CDG".to_string(), to: "LHR".to_string(), line: 1 });
+        let graph = adjacency_list_from_routes(&routes, &airports, DistanceModel::Haversine, LoadMode::Strict).unwrap();
+        assert!(graph.data["LHR"].iter().all(|(code, _)| code == "CDG"));
+
+        let error = load_routes_with_format(ourairports_file, InputFormat::OurAirports, LoadMode::Lenient).unwrap_err();
+        assert!(matches!(error, LoadError::UnsupportedFormat { .. }), "{}", error);
+
+        for path in [openflights_airports, ourairports, openflights_routes] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use crate::airports::Airport;
+use crate::distance::DistanceModel;
+use crate::graph::AdjacencyList;
+use crate::ground::GroundTransfers;
+use crate::great_circle::{intermediate_points, leg_geometry, split_at_antimeridian};
+use crate::itinerary::Itinerary;
+use crate::json::json_string;
+
+// Roughly one interpolated point every 100 kilometers along a great circle
+const DENSIFY_KM: f64 = 100.0;
+
+// GeoJSON positions are [longitude, latitude]
+fn positions(points: &[(f64, f64)]) -> String {
+    let positions: Vec<String> = points.iter().map(|(lat, lon)| format!("[{:.6},{:.6}]", lon, lat)).collect();
+    format!("[{}]", positions.join(","))
+}
+
+// Densified great-circle line between two airports, as a MultiLineString when it crosses the antimeridian
+fn great_circle_geometry(from: &Airport, to: &Airport, distance_km: f64) -> String {
+    let segments = (distance_km / DENSIFY_KM).ceil() as usize;
+    let parts = split_at_antimeridian(&intermediate_points(&from.location, &to.location, segments));
+    if parts.len() == 1 {
+        format!("{{\"type\":\"LineString\",\"coordinates\":{}}}", positions(&parts[0]))
+    } else {
+        let lines: Vec<String> = parts.iter().map(|part| positions(part)).collect();
+        format!("{{\"type\":\"MultiLineString\",\"coordinates\":[{}]}}", lines.join(","))
+    }
+}
+
+fn airport_feature(code: &str, airport: &Airport, extra: &str) -> String {
+    format!(
+        "{{\"type\":\"Feature\",\"geometry\":{{\"type\":\"Point\",\"coordinates\":[{:.6},{:.6}]}},\"properties\":{{\"code\":{},\"name\":{},\"city\":{},\"country\":{}{}}}}}",
+        airport.location.longitude(),
+        airport.location.latitude(),
+        json_string(code),
+        json_string(&airport.name),
+        json_string(&airport.city),
+        json_string(&airport.country),
+        extra,
+    )
+}
+
+fn feature_collection(features: &[String]) -> String {
+    format!("{{\"type\":\"FeatureCollection\",\"features\":[\n{}\n]}}\n", features.join(",\n"))
+}
+
+// Every served airport as a Point (with its number of destinations) and every route as a great-circle line
+pub fn network_geojson(adjacency_list: &AdjacencyList, airports: &HashMap<String, Airport>) -> String {
+    let mut codes: Vec<&String> = adjacency_list.keys().filter(|code| airports.contains_key(*code)).collect();
+    codes.sort();
+
+    let mut features: Vec<String> = Vec::new();
+    for code in &codes {
+        let destinations: HashSet<&String> = adjacency_list[*code].iter().map(|(neighbor, _)| neighbor).collect();
+        features.push(airport_feature(code, &airports[*code], &format!(",\"degree\":{}", destinations.len())));
+    }
+
+    for from in &codes {
+        let mut seen: HashSet<&String> = HashSet::new();
+        for (to, distance) in &adjacency_list[*from] {
+            if *from < to && airports.contains_key(to) && seen.insert(to) {
+                features.push(format!(
+                    "{{\"type\":\"Feature\",\"geometry\":{},\"properties\":{{\"from\":{},\"to\":{},\"distance_km\":{:.2}}}}}",
+                    great_circle_geometry(&airports[*from], &airports[to], distance.km()),
+                    json_string(from),
+                    json_string(to),
+                    distance.km(),
+                ));
+            }
+        }
+    }
+
+    feature_collection(&features)
+}
+
+// The stops of an itinerary as Points and each leg as a great-circle line, labelled flight or ground
+pub fn itinerary_geojson(
+    itinerary: &Itinerary,
+    airports: &HashMap<String, Airport>,
+    ground_transfers: &GroundTransfers,
+    model: DistanceModel,
+) -> String {
+    let mut features: Vec<String> = Vec::new();
+
+    for (stop, code) in itinerary.path.iter().enumerate() {
+        features.push(airport_feature(code, &airports[code], &format!(",\"stop\":{}", stop)));
+    }
+
+    for (leg, pair) in itinerary.path.windows(2).enumerate() {
+        let (from, to) = (&airports[&pair[0]], &airports[&pair[1]]);
+        let geometry = leg_geometry(&from.location, &to.location, 1, model);
+        let mode = if ground_transfers.contains_key(&(pair[0].clone(), pair[1].clone())) { "ground" } else { "flight" };
+        features.push(format!(
+            "{{\"type\":\"Feature\",\"geometry\":{},\"properties\":{{\"leg\":{},\"from\":{},\"to\":{},\"mode\":{},\"distance_km\":{:.2},\
+             \"initial_bearing\":{:.1},\"final_bearing\":{:.1},\"max_latitude\":{:.4},\"min_latitude\":{:.4},\"crosses_antimeridian\":{},\"crosses_equator\":{}}}}}",
+            great_circle_geometry(from, to, geometry.distance_km),
+            leg + 1,
+            json_string(&pair[0]),
+            json_string(&pair[1]),
+            json_string(mode),
+            geometry.distance_km,
+            geometry.initial_bearing,
+            geometry.final_bearing,
+            geometry.max_latitude,
+            geometry.min_latitude,
+            geometry.crosses_antimeridian,
+            geometry.crosses_equator,
+        ));
+    }
+
+    feature_collection(&features)
+}
+
+pub fn write_geojson(filename: &str, contents: &str) -> Result<(), Box<dyn Error>> {
+    let mut output_file = File::create(filename)?;
+    output_file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+    use crate::ground::GroundTransfer;
+
+    fn fiji_and_samoa() -> HashMap<String, Airport> {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("SUV".to_string(), Airport::new("Nausori International Airport", "Nausori", "Fiji", -18.043301, 178.559006));
+        airports.insert("NAN".to_string(), Airport::new("Nadi International Airport", "Nadi", "Fiji", -17.755399703979492, 177.4429931640625));
+        airports.insert("APW".to_string(), Airport::new("Faleolo International Airport", "Faleolo", "Samoa", -13.829969, -172.00833));
+        airports
+    }
+
+    // The single line of the collection holding the feature with this property
+    fn feature<'a>(geojson: &'a str, property: &str) -> &'a str {
+        geojson.lines().find(|line| line.contains(property)).unwrap_or_else(|| panic!("no feature with {}", property))
+    }
+
+    #[test]
+    fn test_network_geojson() {
+        let airports = fiji_and_samoa();
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        for (from, to, km) in [("SUV", "NAN", 122.5), ("SUV", "APW", 1146.0)] {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(km)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(km)));
+        }
+
+        let geojson = network_geojson(&adjacency_list, &airports);
+        assert!(geojson.starts_with("{\"type\":\"FeatureCollection\""));
+        assert_eq!(geojson.matches("\"type\":\"Point\"").count(), 3);
+        assert!(feature(&geojson, "\"code\":\"SUV\"").contains(
+            "\"coordinates\":[178.559006,-18.043301]},\"properties\":{\"code\":\"SUV\",\"name\":\"Nausori International Airport\",\"city\":\"Nausori\",\"country\":\"Fiji\",\"degree\":2}"
+        ));
+        assert!(feature(&geojson, "\"code\":\"APW\"").contains("\"degree\":1"));
+
+        // Each route once, split at ±180° when it crosses the antimeridian
+        let within_fiji = feature(&geojson, "\"from\":\"NAN\",\"to\":\"SUV\"");
+        assert!(within_fiji.contains("\"type\":\"LineString\"") && within_fiji.contains("\"distance_km\":122.50"));
+        let to_samoa = feature(&geojson, "\"from\":\"APW\",\"to\":\"SUV\"");
+        assert!(to_samoa.contains("\"type\":\"MultiLineString\""));
+        assert!(to_samoa.contains("[180.000000,") && to_samoa.contains("[-180.000000,"));
+        assert_eq!(geojson.matches("\"from\":").count(), 2);
+    }
+
+    #[test]
+    fn test_itinerary_geojson() {
+        let airports = fiji_and_samoa();
+        let mut ground_transfers = GroundTransfers::new();
+        ground_transfers.insert(
+            ("NAN".to_string(), "SUV".to_string()),
+            GroundTransfer { distance: Distance::from_km(122.5), penalty: Distance::from_km(50.0) },
+        );
+        let itinerary = Itinerary {
+            origin_airport: "NAN".to_string(),
+            destination_airport: "APW".to_string(),
+            ground_to_origin: Distance::ZERO,
+            ground_from_destination: Distance::ZERO,
+            ground_transfers: Distance::from_km(122.5),
+            transfer_penalty: Distance::from_km(50.0),
+            flight: Distance::from_km(1146.0),
+            path: vec!["NAN".to_string(), "SUV".to_string(), "APW".to_string()],
+        };
+
+        let geojson = itinerary_geojson(&itinerary, &airports, &ground_transfers, DistanceModel::Haversine);
+        assert!(feature(&geojson, "\"code\":\"NAN\"").contains("\"stop\":0"));
+        assert!(feature(&geojson, "\"code\":\"APW\"").contains("\"stop\":2"));
+
+        let transfer = feature(&geojson, "\"leg\":1");
+        assert!(transfer.contains("\"from\":\"NAN\",\"to\":\"SUV\",\"mode\":\"ground\""));
+        assert!(transfer.contains("\"type\":\"LineString\"") && transfer.contains("\"crosses_antimeridian\":false"));
+
+        let flight = feature(&geojson, "\"leg\":2");
+        assert!(flight.contains("\"from\":\"SUV\",\"to\":\"APW\",\"mode\":\"flight\""));
+        assert!(flight.contains("\"type\":\"MultiLineString\""));
+        assert!(flight.contains("\"crosses_antimeridian\":true,\"crosses_equator\":false"));
+        assert!(flight.contains("\"min_latitude\":-18.0433"));
+    }
+}
@@ -1,40 +1,239 @@
-use std::collections::HashMap;
-use std::error::Error;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, BufRead};
 use crate::airports::Airport;
-use geoutils::Location;
+use crate::distance::{distance_with_fallback, Distance, DistanceModel};
+use crate::format::{detect_format, InputFormat};
+use crate::load::{LoadError, LoadMode, LoadReport, Loaded};
 
-pub fn load_adjacency_list_from_csv(
-    filename: &str,
-    airports: &HashMap<String, Airport>,
-) -> Result<HashMap<String, Vec<(String, f64)>>, Box<dyn Error>> {
-    let file = File::open(filename)?;
+// Airport code -> list of (neighbor airport code, distance)
+pub type AdjacencyList = HashMap<String, Vec<(String, Distance)>>;
+
+// One row of routes.csv
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub airline: String,
+    pub from: String,
+    pub to: String,
+    // Line in the routes file, 0 for routes not read from a file
+    pub line: usize,
+}
+
+// Routes in this repo's routes.csv layout or as the original OpenFlights routes.dat
+pub fn load_routes_from_csv(filename: &str, mode: LoadMode) -> Result<Loaded<Vec<Route>>, LoadError> {
+    load_routes_with_format(filename, detect_format(filename)?, mode)
+}
+
+// Columns of the route fields in one of the input formats; routes.dat is routes.csv without the index column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteColumns {
+    pub airline: usize,
+    pub airline_id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub equipment: usize,
+}
+
+impl RouteColumns {
+    pub fn of(filename: &str, format: InputFormat) -> Result<Self, LoadError> {
+        match format {
+            InputFormat::Indexed => Ok(RouteColumns { airline: 1, airline_id: 2, from: 3, to: 5, equipment: 9 }),
+            InputFormat::OpenFlights => Ok(RouteColumns { airline: 0, airline_id: 1, from: 2, to: 4, equipment: 8 }),
+            InputFormat::OurAirports => {
+                let reason = "OurAirports exports have no routes".to_string();
+                Err(LoadError::UnsupportedFormat { path: filename.to_string(), reason })
+            }
+        }
+    }
+}
+
+pub fn load_routes_with_format(filename: &str, format: InputFormat, mode: LoadMode) -> Result<Loaded<Vec<Route>>, LoadError> {
+    let columns = RouteColumns::of(filename, format)?;
+    let io_error = |source| LoadError::Io { path: filename.to_string(), source };
+    let file = File::open(filename).map_err(io_error)?;
     let reader = BufReader::new(file);
-    let mut adjacency_list: HashMap<String, Vec<(String, f64)>> = HashMap::new();
+    let mut routes: Vec<Route> = Vec::new();
+    let mut report = LoadReport::new(filename);
 
-    for line in reader.lines().skip(1) {
-        let record = line?;
+    // Line 1 is the header, if there is one
+    for (index, line) in reader.lines().enumerate().skip(usize::from(format.has_header())) {
+        let record = line.map_err(io_error)?;
         let fields: Vec<_> = record.split(',').collect();
-        if fields.len() >= 6 {
-            let from = fields[3].to_string(); // Source airport
-            let to = fields[5].to_string(); // Destination airport
-
-            // Ensure both source and destination airports exist in the airports map
-            if let (Some(from_airport), Some(to_airport)) = (airports.get(&from), airports.get(&to)) {
-                let distance = from_airport.location.distance_to(&to_airport.location).unwrap(); // Distance in meters
-                let distance_km = distance.meters() as f64 / 1000.0; // Convert to kilometers
-                // Add source airport to destination's neighbor list
-                adjacency_list.entry(from.clone()).or_insert_with(Vec::new).push((to.clone(), distance_km));
-                // Add destination airport to source's neighbor list
-                adjacency_list.entry(to.clone()).or_insert_with(Vec::new).push((from.clone(), distance_km));
-            } else {
-                eprintln!("Missing location data for airports in route: {:?} - {:?}", from, to);
+        report.rows += 1;
+        if fields.len() > columns.to {
+            routes.push(Route {
+                airline: fields[columns.airline].to_string(),
+                from: fields[columns.from].to_string(),
+                to: fields[columns.to].to_string(),
+                line: index + 1,
+            });
+            report.accepted += 1;
+        } else {
+            let reason = format!("expected at least {} fields, found {}", columns.to + 1, fields.len());
+            report.reject(mode, LoadError::MalformedRow { line: index + 1, reason }, &record)?;
+        }
+    }
+
+    Ok(Loaded { data: routes, report })
+}
+
+// Record on each airport how many routes use it
+pub fn count_routes(airports: &mut HashMap<String, Airport>, routes: &[Route]) {
+    for route in routes {
+        for code in [&route.from, &route.to] {
+            if let Some(airport) = airports.get_mut(code) {
+                airport.routes += 1;
             }
+        }
+    }
+}
+
+// Build the undirected adjacency list from routes whose airports both have location data
+pub fn adjacency_list_from_routes(
+    routes: &[Route],
+    airports: &HashMap<String, Airport>,
+    model: DistanceModel,
+    mode: LoadMode,
+) -> Result<Loaded<AdjacencyList>, LoadError> {
+    let mut adjacency_list: AdjacencyList = HashMap::new();
+    let mut report = LoadReport::new("routes");
+    report.rows = routes.len();
+
+    for route in routes {
+        let (from, to) = (&route.from, &route.to);
+
+        // Ensure both source and destination airports exist in the airports map
+        if let (Some(from_airport), Some(to_airport)) = (airports.get(from), airports.get(to)) {
+            // Falls back to haversine where Vincenty does not converge (nearly antipodal airports)
+            let distance = distance_with_fallback(&from_airport.location, &to_airport.location, model);
+            // Add source airport to destination's neighbor list
+            adjacency_list.entry(from.clone()).or_default().push((to.clone(), distance));
+            // Add destination airport to source's neighbor list
+            adjacency_list.entry(to.clone()).or_default().push((from.clone(), distance));
+            report.accepted += 1;
         } else {
-            eprintln!("Skipping line with invalid format: {:?}", record);
+            let text = format!("{},{},{}", route.airline, from, to);
+            for code in [from, to] {
+                if !airports.contains_key(code) {
+                    report.reject(mode, LoadError::UnknownAirport { line: route.line, code: code.clone() }, &text)?;
+                }
+            }
         }
     }
 
-    Ok(adjacency_list)
+    Ok(Loaded { data: adjacency_list, report })
+}
+
+// Collapse repeated routes (one per airline and direction) into a single undirected edge
+// and drop self-loops, so every neighbor appears at most once per airport
+pub fn simple_graph(adjacency_list: &AdjacencyList) -> AdjacencyList {
+    let mut simple: AdjacencyList = HashMap::new();
+
+    for (airport, neighbors) in adjacency_list {
+        let mut seen: HashSet<&String> = HashSet::new();
+        let entry = simple.entry(airport.clone()).or_default();
+        for (neighbor, distance) in neighbors {
+            if neighbor != airport && seen.insert(neighbor) {
+                entry.push((neighbor.clone(), *distance));
+            }
+        }
+    }
+
+    simple
+}
+
+// Split the graph into connected components, largest first
+pub fn connected_components(adjacency_list: &AdjacencyList) -> Vec<Vec<String>> {
+    let mut visited: HashSet<&String> = HashSet::new();
+    let mut components: Vec<Vec<String>> = Vec::new();
+
+    let mut airports: Vec<&String> = adjacency_list.keys().collect();
+    airports.sort();
+
+    for start in airports {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut component = vec![start.clone()];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for (neighbor, _) in &adjacency_list[node] {
+                if adjacency_list.contains_key(neighbor) && visited.insert(neighbor) {
+                    component.push(neighbor.clone());
+                    stack.push(neighbor);
+                }
+            }
+        }
+        component.sort();
+        components.push(component);
+    }
+
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    components
+}
+
+// Restrict the graph to the given set of airports, dropping edges that leave it
+pub fn subgraph(adjacency_list: &AdjacencyList, airports: &HashSet<String>) -> AdjacencyList {
+    adjacency_list
+        .iter()
+        .filter(|(airport, _)| airports.contains(*airport))
+        .map(|(airport, neighbors)| {
+            let kept = neighbors.iter().filter(|(neighbor, _)| airports.contains(neighbor)).cloned().collect();
+            (airport.clone(), kept)
+        })
+        .collect()
+}
+
+// The largest connected component of the deduplicated route graph
+pub fn giant_component(adjacency_list: &AdjacencyList) -> AdjacencyList {
+    let simple = simple_graph(adjacency_list);
+    let largest: HashSet<String> = connected_components(&simple).into_iter().next().unwrap_or_default().into_iter().collect();
+    subgraph(&simple, &largest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_giant_component() {
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("A".to_string(), vec![("B".to_string(), Distance::from_km(1.0)), ("B".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("B".to_string(), vec![("A".to_string(), Distance::from_km(1.0)), ("A".to_string(), Distance::from_km(1.0)), ("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("C".to_string(), vec![("B".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("E".to_string(), vec![("F".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("F".to_string(), vec![("E".to_string(), Distance::from_km(1.0))]);
+
+        let giant = giant_component(&adjacency_list);
+
+        assert_eq!(giant.len(), 3);
+        assert_eq!(giant["B"].len(), 2);
+        assert!(!giant.contains_key("E"));
+    }
+
+    #[test]
+    fn test_strict_and_lenient_loading() {
+        let path = env::temp_dir().join("test_strict_and_lenient_loading_routes.csv");
+        std::fs::write(&path, "index,Airline,Airline ID,Source airport,Source airport ID,Destination airport\n0,BA,1,LHR,1,CDG\n1,BA,1\n2,BA,1,LHR,1,XXX\n").unwrap();
+        let filename = path.to_str().unwrap();
+
+        let error = load_routes_from_csv(filename, LoadMode::Strict).unwrap_err();
+        assert!(matches!(error, LoadError::MalformedRow { line: 3, .. }), "{}", error);
+
+        let loaded = load_routes_from_csv(filename, LoadMode::Lenient).unwrap();
+        assert_eq!(loaded.data.len(), 2);
+        assert_eq!(loaded.report.issues.len(), 1);
+        assert_eq!(loaded.report.issues[0].line(), Some(3));
+
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("CDG".to_string(), Airport::new("Charles de Gaulle International Airport", "Paris", "France", 49.012798, 2.55));
+        let graph = adjacency_list_from_routes(&loaded.data, &airports, DistanceModel::Haversine, LoadMode::Lenient).unwrap();
+        assert_eq!(graph.data.len(), 2);
+        assert!(matches!(&graph.report.issues[..], [LoadError::UnknownAirport { line: 4, code }] if code == "XXX"));
+
+        assert!(matches!(load_routes_from_csv("no_such_file.csv", LoadMode::Lenient), Err(LoadError::Io { .. })));
+        std::fs::remove_file(path).unwrap();
+    }
 }
@@ -0,0 +1,180 @@
+use geoutils::Location;
+use crate::distance::{distance_with_fallback, DistanceModel};
+
+// Points along the great circle from start to end, including both ends, split into `segments` equal arcs
+pub fn intermediate_points(start: &Location, end: &Location, segments: usize) -> Vec<(f64, f64)> {
+    let segments = segments.max(1);
+    let (lat1, lon1) = (start.latitude().to_radians(), start.longitude().to_radians());
+    let (lat2, lon2) = (end.latitude().to_radians(), end.longitude().to_radians());
+
+    // Central angle between the two points (haversine)
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    let angle = 2.0 * h.sqrt().min(1.0).asin();
+    if angle == 0.0 {
+        return vec![(start.latitude(), start.longitude()); segments + 1];
+    }
+
+    (0..=segments)
+        .map(|i| {
+            let fraction = i as f64 / segments as f64;
+            let a = ((1.0 - fraction) * angle).sin() / angle.sin();
+            let b = (fraction * angle).sin() / angle.sin();
+            let x = a * lat1.cos() * lon1.cos() + b * lat2.cos() * lon2.cos();
+            let y = a * lat1.cos() * lon1.sin() + b * lat2.cos() * lon2.sin();
+            let z = a * lat1.sin() + b * lat2.sin();
+            (z.atan2((x * x + y * y).sqrt()).to_degrees(), y.atan2(x).to_degrees())
+        })
+        .collect()
+}
+
+// Split a line of (latitude, longitude) points wherever it crosses the antimeridian, ending and restarting at ±180°
+pub fn split_at_antimeridian(points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let mut parts: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut current: Vec<(f64, f64)> = Vec::new();
+
+    for (i, &(lat, lon)) in points.iter().enumerate() {
+        if i > 0 {
+            let (prev_lat, prev_lon) = points[i - 1];
+            if (lon - prev_lon).abs() > 180.0 {
+                // Unwrap the longitude to find where the segment meets ±180°
+                let edge = if prev_lon > 0.0 { 180.0 } else { -180.0 };
+                let unwrapped = if prev_lon > 0.0 { lon + 360.0 } else { lon - 360.0 };
+                let fraction = (edge - prev_lon) / (unwrapped - prev_lon);
+                let crossing = prev_lat + fraction * (lat - prev_lat);
+                current.push((crossing, edge));
+                parts.push(std::mem::take(&mut current));
+                current.push((crossing, -edge));
+            }
+        }
+        current.push((lat, lon));
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+// Compass bearing in degrees (0-360) when leaving start towards end
+pub fn initial_bearing(start: &Location, end: &Location) -> f64 {
+    let (lat1, lat2) = (start.latitude().to_radians(), end.latitude().to_radians());
+    let delta_lon = (end.longitude() - start.longitude()).to_radians();
+    let y = delta_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+// Compass bearing in degrees (0-360) when arriving at end
+pub fn final_bearing(start: &Location, end: &Location) -> f64 {
+    (initial_bearing(end, start) + 180.0) % 360.0
+}
+
+// Northernmost latitude on the leg: the vertex of the great circle if the leg passes it, otherwise an endpoint
+pub fn max_latitude(start: &Location, end: &Location) -> f64 {
+    let endpoints = start.latitude().max(end.latitude());
+    let initial = initial_bearing(start, end);
+    let arrival = final_bearing(start, end);
+    let heading_north = !(90.0..=270.0).contains(&initial);
+    let arriving_south = arrival > 90.0 && arrival < 270.0;
+    if heading_north && arriving_south {
+        // Clairaut's relation: cos(vertex latitude) = |sin(bearing) * cos(latitude)|
+        let vertex = (initial.to_radians().sin() * start.latitude().to_radians().cos()).abs().min(1.0).acos().to_degrees();
+        vertex.max(endpoints)
+    } else {
+        endpoints
+    }
+}
+
+// Southernmost latitude on the leg, the northernmost of the leg mirrored across the equator
+pub fn min_latitude(start: &Location, end: &Location) -> f64 {
+    let mirror = |location: &Location| Location::new(-location.latitude(), location.longitude());
+    -max_latitude(&mirror(start), &mirror(end))
+}
+
+pub fn crosses_antimeridian(start: &Location, end: &Location) -> bool {
+    split_at_antimeridian(&intermediate_points(start, end, 64)).len() > 1
+}
+
+// A great-circle leg shorter than half the globe meets the equator at most once
+pub fn crosses_equator(start: &Location, end: &Location) -> bool {
+    start.latitude() * end.latitude() < 0.0
+}
+
+// Geometry of one leg of an itinerary
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegGeometry {
+    pub distance_km: f64,
+    pub initial_bearing: f64,
+    pub final_bearing: f64,
+    pub max_latitude: f64,
+    pub min_latitude: f64,
+    pub crosses_antimeridian: bool,
+    pub crosses_equator: bool,
+    // (latitude, longitude) points along the great circle, including both airports
+    pub waypoints: Vec<(f64, f64)>,
+}
+
+// Geometry of the leg from start to end, measured with the same model as the route graph
+pub fn leg_geometry(start: &Location, end: &Location, segments: usize, model: DistanceModel) -> LegGeometry {
+    LegGeometry {
+        distance_km: distance_with_fallback(start, end, model).km(),
+        initial_bearing: initial_bearing(start, end),
+        final_bearing: final_bearing(start, end),
+        max_latitude: max_latitude(start, end),
+        min_latitude: min_latitude(start, end),
+        crosses_antimeridian: crosses_antimeridian(start, end),
+        crosses_equator: crosses_equator(start, end),
+        waypoints: intermediate_points(start, end, segments),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airports::Airport;
+
+    #[test]
+    fn test_great_circle_split_at_antimeridian() {
+        let suva = Airport::new("Nausori International Airport", "Nausori", "Fiji", -18.043301, 178.559006);
+        let apia = Airport::new("Faleolo International Airport", "Faleolo", "Samoa", -13.829969, -172.00833);
+
+        let points = intermediate_points(&suva.location, &apia.location, 10);
+        assert_eq!(points.len(), 11);
+        assert!((points[10].1 - -172.00833).abs() < 1e-6);
+
+        let parts = split_at_antimeridian(&points);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].last().unwrap().1, 180.0);
+        assert_eq!(parts[1][0].1, -180.0);
+        assert_eq!(parts[0].last().unwrap().0, parts[1][0].0);
+    }
+
+    #[test]
+    fn test_leg_geometry() {
+        let jfk = Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789);
+        let lhr = Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941);
+        let syd = Airport::new("Sydney Kingsford Smith International Airport", "Sydney", "Australia", -33.94609832763672, 151.177001953125);
+        let lax = Airport::new("Los Angeles International Airport", "Los Angeles", "United States", 33.94250107, -118.4079971);
+        let scl = Airport::new("Comodoro Arturo Merino Benítez International Airport", "Santiago", "Chile", -33.393001556396484, -70.78579711914062);
+
+        let leg = leg_geometry(&jfk.location, &lhr.location, 4, DistanceModel::Vincenty);
+        assert_eq!(leg.distance_km, distance_with_fallback(&jfk.location, &lhr.location, DistanceModel::Vincenty).km());
+        assert!((leg.initial_bearing - 51.4).abs() < 0.5);
+        assert!((leg.final_bearing - 107.9).abs() < 0.5);
+        // The vertex of the great circle lies north of both airports
+        assert!((leg.max_latitude - 53.66).abs() < 0.01);
+        assert_eq!(leg.min_latitude, jfk.location.latitude());
+        assert!(!leg.crosses_equator && !leg.crosses_antimeridian);
+        assert_eq!(leg.waypoints.len(), 5);
+
+        let leg = leg_geometry(&lax.location, &syd.location, 4, DistanceModel::Haversine);
+        assert!(leg.crosses_equator && leg.crosses_antimeridian);
+        assert_eq!(leg.max_latitude, lax.location.latitude());
+        assert_eq!(leg.min_latitude, syd.location.latitude());
+
+        // A southern-hemisphere leg bends toward the pole, south of both airports
+        let leg = leg_geometry(&syd.location, &scl.location, 4, DistanceModel::Haversine);
+        assert!(leg.min_latitude < -55.0, "{}", leg.min_latitude);
+        assert_eq!(leg.max_latitude, scl.location.latitude());
+    }
+}
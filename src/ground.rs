@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+use crate::airports::Airport;
+use crate::distance::Distance;
+use crate::graph::AdjacencyList;
+use crate::spatial::SpatialIndex;
+
+// A synthetic surface transfer: its ground distance and the penalty added to its edge weight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundTransfer {
+    pub distance: Distance,
+    pub penalty: Distance,
+}
+
+// Ordered airport pairs connected by a surface transfer rather than a flight
+pub type GroundTransfers = HashMap<(String, String), GroundTransfer>;
+
+// Connect airports within `max_km` of each other that have no direct flight, at ground distance plus `penalty_km`
+pub fn add_ground_transfers(
+    adjacency_list: &mut AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    max_km: f64,
+    penalty_km: f64,
+) -> GroundTransfers {
+    let index = SpatialIndex::new(airports);
+    let mut transfers: GroundTransfers = HashMap::new();
+
+    let mut served: Vec<String> = adjacency_list.keys().filter(|code| airports.contains_key(*code)).cloned().collect();
+    served.sort();
+
+    for code in &served {
+        let location = airports[code].location;
+        let flights: HashSet<&String> = adjacency_list[code].iter().map(|(neighbor, _)| neighbor).collect();
+        let nearby: Vec<(String, f64)> = index
+            .within(location.latitude(), location.longitude(), max_km)
+            .into_iter()
+            .filter(|(other, _)| other != code && adjacency_list.contains_key(other) && !flights.contains(other))
+            .collect();
+
+        for (other, distance) in nearby {
+            // Both directions are added when each end is visited
+            adjacency_list.get_mut(code).unwrap().push((other.clone(), Distance::from_km(distance + penalty_km)));
+            transfers.insert(
+                (code.clone(), other),
+                GroundTransfer { distance: Distance::from_km(distance), penalty: Distance::from_km(penalty_km) },
+            );
+        }
+    }
+
+    transfers
+}
+
+// Path as "EWR -[ground]-> JFK -> LHR", marking the legs travelled by ground
+pub fn format_path(path: &[String], transfers: &GroundTransfers) -> String {
+    let mut formatted = String::new();
+    for (i, airport) in path.iter().enumerate() {
+        if i > 0 {
+            let leg = (path[i - 1].clone(), airport.clone());
+            formatted.push_str(if transfers.contains_key(&leg) { " -[ground]-> " } else { " -> " });
+        }
+        formatted.push_str(airport);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bfs;
+
+    #[test]
+    fn test_ground_transfers() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("EWR".to_string(), Airport::new("Newark Liberty International Airport", "Newark", "United States", 40.692501068115234, -74.168701171875));
+        airports.insert("JFK".to_string(), Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789));
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("ORD".to_string(), Airport::new("Chicago O'Hare International Airport", "Chicago", "United States", 41.9786, -87.9048));
+
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        let routes = [("EWR", "ORD", 1150.0), ("JFK", "LHR", 5540.0)];
+        for (from, to, distance) in routes {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(distance)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(distance)));
+        }
+
+        let transfers = add_ground_transfers(&mut adjacency_list, &airports, 50.0, 100.0);
+        assert_eq!(transfers.len(), 2);
+
+        let (distances, previous) = bfs::shortest_path_tree(&adjacency_list, "ORD");
+        let path = bfs::reconstruct_path(&previous, "ORD", "LHR");
+        assert_eq!(format_path(&path, &transfers), "ORD -> EWR -[ground]-> JFK -> LHR");
+        assert!(distances["LHR"].km() > 1150.0 + 5540.0 + 100.0);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use geoutils::Location;
+use crate::airports::Airport;
+use crate::bfs::{multi_source_shortest_path_tree, reconstruct_path_from_any};
+use crate::distance::{haversine_km, Distance};
+use crate::graph::AdjacencyList;
+use crate::ground::GroundTransfers;
+use crate::search::{did_you_mean, resolve_airport, search_airports};
+use crate::spatial::SpatialIndex;
+
+// Airports of a city further than this from the bulk of its airports are taken to be mis-located
+const CITY_SPREAD_KM: f64 = 100.0;
+
+// Where a trip starts or ends
+#[derive(Debug, Clone, PartialEq)]
+pub enum Place {
+    Airport(String),
+    Coordinates(f64, f64),
+    // City name, optionally followed by ", Country" to pick between cities of the same name
+    City(String),
+}
+
+impl Place {
+    // An IATA or ICAO airport code if it is one, then "lat,lon", otherwise a city name; text that looks like
+    // a code but is neither an airport nor a city is an unknown airport
+    pub fn parse(text: &str, airports: &HashMap<String, Airport>) -> Result<Place, Box<dyn Error>> {
+        let text = text.trim();
+        let unknown_code = match resolve_airport(airports, text) {
+            Ok(code) => return Ok(Place::Airport(code)),
+            Err(error) => error,
+        };
+        if let Some((lat, lon)) = text.split_once(',') {
+            if let (Ok(latitude), Ok(longitude)) = (lat.trim().parse::<f64>(), lon.trim().parse::<f64>()) {
+                return Ok(Place::Coordinates(latitude, longitude));
+            }
+        }
+        let looks_like_code = (3..=4).contains(&text.len()) && text.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        if looks_like_code && !airports.values().any(|airport| airport.city.eq_ignore_ascii_case(text)) {
+            return Err(unknown_code);
+        }
+        Ok(Place::City(text.to_string()))
+    }
+
+    // Coordinate of the place; a city resolves to the center of its airports
+    pub fn location(&self, airports: &HashMap<String, Airport>) -> Result<Location, Box<dyn Error>> {
+        match self {
+            Place::Airport(code) => Ok(airports[code].location),
+            Place::Coordinates(latitude, longitude) => Ok(Location::new(*latitude, *longitude)),
+            Place::City(name) => {
+                let locations: Vec<&Location> = city_airports(name, airports)?.iter().map(|(_, airport)| &airport.location).collect();
+                Ok(city_center(&locations))
+            }
+        }
+    }
+}
+
+// Airports of a city given as "City" or "City, Country"
+fn city_airports<'a>(name: &str, airports: &'a HashMap<String, Airport>) -> Result<Vec<(&'a String, &'a Airport)>, Box<dyn Error>> {
+    let (city, country) = match name.split_once(',') {
+        Some((city, country)) => (city.trim(), Some(country.trim())),
+        None => (name, None),
+    };
+    let matches: Vec<(&String, &Airport)> = airports
+        .iter()
+        .filter(|(_, airport)| airport.city.eq_ignore_ascii_case(city))
+        .filter(|(_, airport)| country.is_none_or(|country| airport.country.eq_ignore_ascii_case(country)))
+        .collect();
+
+    let countries: HashSet<&str> = matches.iter().map(|(_, airport)| airport.country.as_str()).collect();
+    if matches.is_empty() {
+        let candidates = search_airports(airports, name, 5);
+        return Err(format!("No airports found in city: {}{}", name, did_you_mean(airports, &candidates)).into());
+    }
+    if countries.len() > 1 {
+        let mut countries: Vec<&str> = countries.into_iter().collect();
+        countries.sort();
+        return Err(format!("City {} exists in several countries, use \"{}, Country\" with one of: {}", city, city, countries.join(", ")).into());
+    }
+    Ok(matches)
+}
+
+// Center of a city's airports that a few mis-located rows cannot drag away: the median latitude and longitude,
+// then the center of the airports within CITY_SPREAD_KM of it
+fn city_center(locations: &[&Location]) -> Location {
+    let median = |mut values: Vec<f64>| {
+        values.sort_by(f64::total_cmp);
+        values[values.len() / 2]
+    };
+    let median_location = Location::new(
+        median(locations.iter().map(|location| location.latitude()).collect()),
+        median(locations.iter().map(|location| location.longitude()).collect()),
+    );
+    let core: Vec<&Location> = locations.iter().copied().filter(|location| haversine_km(&median_location, location) <= CITY_SPREAD_KM).collect();
+    if core.is_empty() {
+        median_location
+    } else {
+        Location::center(&core)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Itinerary {
+    pub origin_airport: String,
+    pub destination_airport: String,
+    // Distance by ground to the first airport and from the last
+    pub ground_to_origin: Distance,
+    pub ground_from_destination: Distance,
+    // Surface transfers between airports along the path, and the penalty they added to the search cost
+    pub ground_transfers: Distance,
+    pub transfer_penalty: Distance,
+    pub flight: Distance,
+    pub path: Vec<String>,
+}
+
+impl Itinerary {
+    // Distance travelled; the transfer penalty only steers the search
+    pub fn total(&self) -> Distance {
+        self.ground_to_origin + self.flight + self.ground_transfers + self.ground_from_destination
+    }
+}
+
+// Airports with routes within the radius of a place, with their ground distance; an airport place and the
+// served airports of a city place are always included
+fn candidate_airports(
+    place: &Place,
+    adjacency_list: &AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    index: &SpatialIndex,
+    radius_km: f64,
+) -> Result<Vec<(String, Distance)>, Box<dyn Error>> {
+    let location = place.location(airports)?;
+    let mut candidates: Vec<(String, Distance)> = index
+        .within(location.latitude(), location.longitude(), radius_km)
+        .into_iter()
+        .filter(|(code, _)| adjacency_list.contains_key(code))
+        .map(|(code, km)| (code, Distance::from_km(km)))
+        .collect();
+    match place {
+        Place::Airport(code) => {
+            candidates.retain(|(candidate, _)| candidate != code);
+            candidates.insert(0, (code.clone(), Distance::ZERO));
+        }
+        Place::City(name) => {
+            for (code, airport) in city_airports(name, airports)? {
+                if adjacency_list.contains_key(code) && !candidates.iter().any(|(candidate, _)| candidate == code) {
+                    candidates.push((code.clone(), Distance::from_km(haversine_km(&location, &airport.location))));
+                }
+            }
+        }
+        Place::Coordinates(..) => {}
+    }
+    Ok(candidates)
+}
+
+// Best itinerary between two places, minimising ground distance plus flown distance
+pub fn plan_itinerary(
+    adjacency_list: &AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    index: &SpatialIndex,
+    origin: &Place,
+    destination: &Place,
+    ground_transfers: &GroundTransfers,
+    radius_km: f64,
+) -> Result<Option<Itinerary>, Box<dyn Error>> {
+    let origins = candidate_airports(origin, adjacency_list, airports, index, radius_km)?;
+    let destinations = candidate_airports(destination, adjacency_list, airports, index, radius_km)?;
+    if origins.is_empty() {
+        return Err(format!("No airports with routes within {} kilometers of the origin", radius_km).into());
+    }
+    if destinations.is_empty() {
+        return Err(format!("No airports with routes within {} kilometers of the destination", radius_km).into());
+    }
+
+    // Ground distance to each origin airport is the starting cost of the search
+    let (distances, previous) = multi_source_shortest_path_tree(adjacency_list, &origins);
+    let ground_to: HashMap<&String, Distance> = origins.iter().map(|(code, distance)| (code, *distance)).collect();
+
+    let best = destinations
+        .iter()
+        .filter_map(|(code, ground_from)| distances.get(code).map(|reached| (code, *ground_from, *reached + *ground_from)))
+        .min_by(|a, b| a.2.total_cmp(&b.2).then_with(|| a.0.cmp(b.0)));
+
+    Ok(best.map(|(code, ground_from_destination, _)| {
+        let path = reconstruct_path_from_any(&previous, code);
+        let ground_to_origin = ground_to[&path[0]];
+        let (mut transfers, mut penalty) = (Distance::ZERO, Distance::ZERO);
+        for pair in path.windows(2) {
+            if let Some(transfer) = ground_transfers.get(&(pair[0].clone(), pair[1].clone())) {
+                transfers += transfer.distance;
+                penalty += transfer.penalty;
+            }
+        }
+        Itinerary {
+            origin_airport: path[0].clone(),
+            destination_airport: code.clone(),
+            ground_to_origin,
+            ground_from_destination,
+            ground_transfers: transfers,
+            transfer_penalty: penalty,
+            flight: distances[code] - ground_to_origin - transfers - penalty,
+            path,
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ground::add_ground_transfers;
+
+    #[test]
+    fn test_plan_itinerary_from_coordinates() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("LGW".to_string(), Airport::new("London Gatwick Airport", "London", "United Kingdom", 51.148102, -0.190278));
+        airports.insert("CDG".to_string(), Airport::new("Charles de Gaulle International Airport", "Paris", "France", 49.012798, 2.55));
+        airports.insert("ORY".to_string(), Airport::new("Paris-Orly Airport", "Paris", "France", 48.7233333, 2.3794444));
+        airports.insert("YXU".to_string(), Airport::new("London Airport", "London", "Canada", 43.035599, -81.1539));
+
+        // Only LGW flies to Paris, so it wins even though LHR is closer to the origin
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("LHR".to_string(), vec![("YXU".to_string(), Distance::from_km(5500.0))]);
+        adjacency_list.insert("YXU".to_string(), vec![("LHR".to_string(), Distance::from_km(5500.0))]);
+        adjacency_list.insert("LGW".to_string(), vec![("ORY".to_string(), Distance::from_km(320.0))]);
+        adjacency_list.insert("ORY".to_string(), vec![("LGW".to_string(), Distance::from_km(320.0))]);
+
+        let index = SpatialIndex::new(&airports);
+        let origin = Place::parse("51.5074,-0.1278", &airports).unwrap();
+        let destination = Place::parse("Paris", &airports).unwrap();
+        assert_eq!(origin, Place::Coordinates(51.5074, -0.1278));
+
+        let itinerary = plan_itinerary(&adjacency_list, &airports, &index, &origin, &destination, &GroundTransfers::new(), 100.0).unwrap().unwrap();
+        assert_eq!(itinerary.path, vec!["LGW", "ORY"]);
+        assert_eq!(itinerary.flight, Distance::from_km(320.0));
+        assert!(itinerary.ground_to_origin.km() > 30.0 && itinerary.ground_to_origin.km() < 50.0);
+
+        // London exists in two countries
+        assert!(Place::parse("London", &airports).unwrap().location(&airports).is_err());
+        assert!(Place::parse("london, canada", &airports).unwrap().location(&airports).is_ok());
+    }
+
+    #[test]
+    fn test_city_with_mislocated_airport() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("JFK".to_string(), Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789));
+        airports.insert("LGA".to_string(), Airport::new("La Guardia Airport", "New York", "United States", 40.777199, -73.872597));
+        airports.insert("JRB".to_string(), Airport::new("Downtown-Manhattan/Wall St Heliport", "New York", "United States", 40.701199, -74.009003));
+        // Listed with City "New York" but in Mississippi
+        airports.insert("KIDL".to_string(), Airport::new("Indianola Municipal Airport", "New York", "United States", 33.485699, -90.678902));
+        airports.insert("MDT".to_string(), Airport::new("Harrisburg International Airport", "Harrisburg", "United States", 40.193501, -76.763397));
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        for (from, to, km) in [("JFK", "LHR", 5540.0), ("MDT", "LHR", 5700.0)] {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(km)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(km)));
+        }
+
+        let index = SpatialIndex::new(&airports);
+        let origin = Place::parse("New York", &airports).unwrap();
+        let center = origin.location(&airports).unwrap();
+        assert!((center.latitude() - 40.7).abs() < 0.2 && (center.longitude() + 73.9).abs() < 0.2, "{:?}", center);
+
+        // JFK is a candidate even with a radius that would miss it
+        let itinerary = plan_itinerary(&adjacency_list, &airports, &index, &origin, &Place::parse("LHR", &airports).unwrap(), &GroundTransfers::new(), 5.0).unwrap().unwrap();
+        assert_eq!(itinerary.path, vec!["JFK", "LHR"]);
+        assert!(itinerary.ground_to_origin.km() < 30.0);
+    }
+
+    #[test]
+    fn test_itinerary_with_ground_transfer() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("EWR".to_string(), Airport::new("Newark Liberty International Airport", "Newark", "United States", 40.692501068115234, -74.168701171875));
+        airports.insert("JFK".to_string(), Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789));
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("ORD".to_string(), Airport::new("Chicago O'Hare International Airport", "Chicago", "United States", 41.9786, -87.9048));
+
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        for (from, to, km) in [("EWR", "ORD", 1150.0), ("JFK", "LHR", 5540.0)] {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(km)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(km)));
+        }
+        let transfers = add_ground_transfers(&mut adjacency_list, &airports, 50.0, 100.0);
+
+        let index = SpatialIndex::new(&airports);
+        let origin = Place::parse("ORD", &airports).unwrap();
+        let destination = Place::parse("LHR", &airports).unwrap();
+        let itinerary = plan_itinerary(&adjacency_list, &airports, &index, &origin, &destination, &transfers, 5.0).unwrap().unwrap();
+        assert_eq!(itinerary.path, vec!["ORD", "EWR", "JFK", "LHR"]);
+
+        // The EWR-JFK transfer is neither flown nor counted with its penalty in the total
+        let ground_km = haversine_km(&airports["EWR"].location, &airports["JFK"].location);
+        assert!((itinerary.flight.km() - (1150.0 + 5540.0)).abs() < 1e-6);
+        assert!((itinerary.ground_transfers.km() - ground_km).abs() < 1e-6);
+        assert_eq!(itinerary.transfer_penalty, Distance::from_km(100.0));
+        assert!((itinerary.total().km() - (1150.0 + 5540.0 + ground_km)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::graph::{simple_graph, subgraph, AdjacencyList};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreDecomposition {
+    pub core_numbers: HashMap<String, usize>,
+    // Highest k with a non-empty k-core, and the airports in it
+    pub innermost_core: usize,
+    pub innermost: Vec<String>,
+    // Number of airports whose core number is exactly k, for every k
+    pub shell_sizes: BTreeMap<usize, usize>,
+}
+
+// Core number of every airport: the largest k such that it belongs to a subgraph where every airport has at least k routes
+pub fn core_numbers(adjacency_list: &AdjacencyList) -> HashMap<String, usize> {
+    let simple = simple_graph(adjacency_list);
+    let mut degree: HashMap<&String, usize> = simple.iter().map(|(airport, neighbors)| (airport, neighbors.len())).collect();
+    let max_degree = degree.values().copied().max().unwrap_or(0);
+
+    // Airports bucketed by their current degree, peeled from the lowest bucket upwards
+    let mut buckets: Vec<HashSet<&String>> = vec![HashSet::new(); max_degree + 1];
+    for (&airport, &d) in &degree {
+        buckets[d].insert(airport);
+    }
+
+    let mut cores: HashMap<String, usize> = HashMap::new();
+    let mut k = 0;
+    while cores.len() < simple.len() {
+        let Some(level) = (0..=max_degree).find(|&d| !buckets[d].is_empty()) else {
+            break;
+        };
+        k = k.max(level);
+        let airport = *buckets[level].iter().min().unwrap();
+        buckets[level].remove(airport);
+        cores.insert(airport.clone(), k);
+
+        for (neighbor, _) in &simple[airport] {
+            if cores.contains_key(neighbor) {
+                continue;
+            }
+            let d = degree[neighbor];
+            if d > level {
+                buckets[d].remove(neighbor);
+                buckets[d - 1].insert(neighbor);
+                degree.insert(neighbor, d - 1);
+            }
+        }
+    }
+
+    cores
+}
+
+// Core numbers with the innermost core and the size of every shell
+pub fn core_decomposition(adjacency_list: &AdjacencyList) -> CoreDecomposition {
+    let core_numbers = core_numbers(adjacency_list);
+    let innermost_core = core_numbers.values().copied().max().unwrap_or(0);
+
+    let mut innermost: Vec<String> = core_numbers
+        .iter()
+        .filter(|(_, &k)| k == innermost_core)
+        .map(|(airport, _)| airport.clone())
+        .collect();
+    innermost.sort();
+
+    let mut shell_sizes: BTreeMap<usize, usize> = BTreeMap::new();
+    for &k in core_numbers.values() {
+        *shell_sizes.entry(k).or_default() += 1;
+    }
+
+    CoreDecomposition { core_numbers, innermost_core, innermost, shell_sizes }
+}
+
+// The k-core: the backbone left after repeatedly removing airports with fewer than k routes
+pub fn k_core(adjacency_list: &AdjacencyList, k: usize) -> AdjacencyList {
+    let backbone: HashSet<String> = core_numbers(adjacency_list)
+        .into_iter()
+        .filter(|(_, core)| *core >= k)
+        .map(|(airport, _)| airport)
+        .collect();
+    subgraph(&simple_graph(adjacency_list), &backbone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_core_decomposition() {
+        // Complete graph on A, B, C, D with a tail D - E - F
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        let routes = [("A", "B"), ("A", "C"), ("A", "D"), ("B", "C"), ("B", "D"), ("C", "D"), ("D", "E"), ("E", "F")];
+        for (from, to) in routes {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(1.0)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(1.0)));
+        }
+
+        let decomposition = core_decomposition(&adjacency_list);
+
+        assert_eq!(decomposition.innermost_core, 3);
+        assert_eq!(decomposition.innermost, vec!["A", "B", "C", "D"]);
+        assert_eq!(decomposition.core_numbers["E"], 1);
+        assert_eq!(decomposition.shell_sizes.get(&1), Some(&2));
+        assert_eq!(k_core(&adjacency_list, 2).len(), 4);
+    }
+}
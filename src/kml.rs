@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use crate::airports::Airport;
+use crate::distance::haversine_km;
+use crate::export::xml_escape;
+use crate::ground::GroundTransfers;
+use crate::great_circle::intermediate_points;
+use crate::itinerary::Itinerary;
+
+const FEET_TO_METERS: f64 = 0.3048;
+// Typical cruising altitude (35,000 ft), reached on legs of 500 km or more
+const CRUISE_METERS: f64 = 10668.0;
+
+// Balloon shown when an airport placemark is clicked
+fn airport_description(code: &str, airport: &Airport) -> String {
+    format!(
+        "<b>{}</b> ({})<br/>{}, {}<br/>Altitude: {:.0} ft",
+        airport.name, code, airport.city, airport.country, airport.altitude
+    )
+}
+
+// Great-circle leg rising from the departure airport to cruise and back down to the arrival airport
+fn leg_coordinates(from: &Airport, to: &Airport) -> String {
+    let distance_km = haversine_km(&from.location, &to.location);
+    let segments = ((distance_km / 50.0).ceil() as usize).max(2);
+    let cruise = CRUISE_METERS * (distance_km / 500.0).min(1.0);
+    let (start, end) = (from.altitude * FEET_TO_METERS, to.altitude * FEET_TO_METERS);
+
+    let points = intermediate_points(&from.location, &to.location, segments);
+    let coordinates: Vec<String> = points
+        .iter()
+        .enumerate()
+        .map(|(i, (lat, lon))| {
+            let fraction = i as f64 / segments as f64;
+            let altitude = start + (end - start) * fraction + cruise * (std::f64::consts::PI * fraction).sin();
+            format!("{:.6},{:.6},{:.0}", lon, lat, altitude)
+        })
+        .collect();
+    coordinates.join(" ")
+}
+
+// KML document with a placemark per stop and an extruded great-circle path per leg
+pub fn itinerary_kml(
+    itinerary: &Itinerary,
+    airports: &HashMap<String, Airport>,
+    ground_transfers: &GroundTransfers,
+) -> String {
+    let title = format!("{} to {}", itinerary.origin_airport, itinerary.destination_airport);
+    let mut kml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+    kml.push_str(&format!("  <name>{}</name>\n", xml_escape(&title)));
+    kml.push_str(&format!("  <description>{:.0} km in {} legs</description>\n", itinerary.flight.km(), itinerary.path.len().saturating_sub(1)));
+    kml.push_str("  <Style id=\"flight\"><LineStyle><color>ff2b39c0</color><width>3</width></LineStyle><PolyStyle><color>402b39c0</color></PolyStyle></Style>\n");
+    kml.push_str("  <Style id=\"ground\"><LineStyle><color>ff3c8c2e</color><width>3</width></LineStyle></Style>\n");
+
+    for code in &itinerary.path {
+        let airport = &airports[code];
+        kml.push_str("  <Placemark>\n");
+        kml.push_str(&format!("    <name>{}</name>\n", xml_escape(code)));
+        kml.push_str(&format!("    <description>{}</description>\n", xml_escape(&airport_description(code, airport))));
+        kml.push_str(&format!(
+            "    <Point><coordinates>{:.6},{:.6},{:.0}</coordinates></Point>\n",
+            airport.location.longitude(),
+            airport.location.latitude(),
+            airport.altitude * FEET_TO_METERS
+        ));
+        kml.push_str("  </Placemark>\n");
+    }
+
+    for pair in itinerary.path.windows(2) {
+        let (from, to) = (&airports[&pair[0]], &airports[&pair[1]]);
+        kml.push_str("  <Placemark>\n");
+        kml.push_str(&format!("    <name>{} - {}</name>\n", xml_escape(&pair[0]), xml_escape(&pair[1])));
+        if ground_transfers.contains_key(&(pair[0].clone(), pair[1].clone())) {
+            // Surface transfers stay on the ground
+            kml.push_str("    <styleUrl>#ground</styleUrl>\n    <LineString><tessellate>1</tessellate><altitudeMode>clampToGround</altitudeMode>\n");
+            kml.push_str(&format!(
+                "      <coordinates>{:.6},{:.6},0 {:.6},{:.6},0</coordinates>\n",
+                from.location.longitude(),
+                from.location.latitude(),
+                to.location.longitude(),
+                to.location.latitude()
+            ));
+        } else {
+            kml.push_str("    <styleUrl>#flight</styleUrl>\n    <LineString><extrude>1</extrude><tessellate>1</tessellate><altitudeMode>absolute</altitudeMode>\n");
+            kml.push_str(&format!("      <coordinates>{}</coordinates>\n", leg_coordinates(from, to)));
+        }
+        kml.push_str("    </LineString>\n  </Placemark>\n");
+    }
+
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+    use crate::itinerary;
+
+    #[test]
+    fn test_itinerary_kml() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("DEN".to_string(), Airport { altitude: 5431.0, ..Airport::new("Denver International Airport", "Denver", "United States", 39.861698150635, -104.672996521) });
+        airports.insert("ORD".to_string(), Airport { altitude: 672.0, ..Airport::new("Chicago O'Hare International Airport", "Chicago", "United States", 41.9786, -87.9048) });
+
+        let itinerary = itinerary::Itinerary {
+            origin_airport: "DEN".to_string(),
+            destination_airport: "ORD".to_string(),
+            ground_to_origin: Distance::ZERO,
+            ground_from_destination: Distance::ZERO,
+            ground_transfers: Distance::ZERO,
+            transfer_penalty: Distance::ZERO,
+            flight: Distance::from_km(1424.0),
+            path: vec!["DEN".to_string(), "ORD".to_string()],
+        };
+
+        let kml = itinerary_kml(&itinerary, &airports, &GroundTransfers::new());
+
+        assert_eq!(kml.matches("<Placemark>").count(), 3);
+        assert!(kml.contains("<extrude>1</extrude>"));
+        assert!(kml.contains("Altitude: 5431 ft"));
+        assert!(kml.contains("Chicago O'Hare International Airport"));
+        // The leg starts at Denver's elevation in meters
+        assert!(kml.contains("-104.672997,39.861698,1655 "));
+    }
+}
@@ -0,0 +1,38 @@
+// Flight route network analysis: loading airports and routes, graph algorithms, itinerary
+// planning, map and graph exports, and data validation
+pub mod airports;
+pub mod graph;
+pub mod bfs;
+pub mod metrics;
+pub mod critical;
+pub mod robustness;
+pub mod community;
+pub mod small_world;
+pub mod kcore;
+pub mod mst;
+pub mod spatial;
+pub mod itinerary;
+pub mod metro;
+pub mod ground;
+pub mod great_circle;
+pub mod geojson;
+pub mod json;
+pub mod svg;
+pub mod export;
+pub mod kml;
+pub mod distance;
+pub mod search;
+pub mod load;
+pub mod airlines;
+pub mod aircraft;
+pub mod validation;
+pub mod airport_checks;
+pub mod format;
+
+pub use airports::{load_airports_from_csv, load_airports_with_format, Airport};
+pub use distance::{Distance, DistanceModel, DistanceUnit};
+pub use load::{LoadError, LoadMode, LoadReport, Loaded};
+pub use format::{detect_format, InputFormat};
+pub use graph::{adjacency_list_from_routes, load_routes_from_csv, load_routes_with_format, AdjacencyList, Route};
+pub use search::{resolve_airport, search_airports};
+pub use validation::{validate_routes, RouteRule};
@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use crate::json::json_string;
+
+// Rejected rows kept verbatim for each reason
+const SAMPLES_PER_REASON: usize = 3;
+
+// Why a data file, or one of its rows, could not be loaded; rows are numbered from 1 including the header
+#[derive(Debug)]
+pub enum LoadError {
+    Io { path: String, source: io::Error },
+    MalformedRow { line: usize, reason: String },
+    BadCoordinate { line: usize, field: &'static str, value: String },
+    UnknownAirport { line: usize, code: String },
+    DuplicateKey { line: usize, key: String },
+    UnsupportedFormat { path: String, reason: String },
+}
+
+impl LoadError {
+    // Line of the offending row, if the error is about a row rather than the whole file
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            LoadError::Io { .. } | LoadError::UnsupportedFormat { .. } => None,
+            LoadError::MalformedRow { line, .. }
+            | LoadError::BadCoordinate { line, .. }
+            | LoadError::UnknownAirport { line, .. }
+            | LoadError::DuplicateKey { line, .. } => Some(*line),
+        }
+    }
+
+    // Short name of the rejection reason, used to group issues in a LoadReport
+    pub fn reason(&self) -> &'static str {
+        match self {
+            LoadError::Io { .. } => "io",
+            LoadError::MalformedRow { .. } => "malformed row",
+            LoadError::BadCoordinate { .. } => "bad coordinate",
+            LoadError::UnknownAirport { .. } => "unknown airport",
+            LoadError::DuplicateKey { .. } => "duplicate key",
+            LoadError::UnsupportedFormat { .. } => "unsupported format",
+        }
+    }
+
+    // A csv reader error: I/O problems stay I/O errors, anything else is a malformed row
+    pub fn from_csv(path: &str, error: csv::Error) -> LoadError {
+        let line = error.position().map(|position| position.line() as usize).unwrap_or(0);
+        let reason = error.to_string();
+        match error.into_kind() {
+            csv::ErrorKind::Io(source) => LoadError::Io { path: path.to_string(), source },
+            _ => LoadError::MalformedRow { line, reason },
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io { path, source } => write!(f, "Cannot read {}: {}", path, source),
+            LoadError::MalformedRow { line, reason } => write!(f, "Line {}: malformed row ({})", line, reason),
+            LoadError::BadCoordinate { line, field, value } => write!(f, "Line {}: invalid {} {:?}", line, field, value),
+            LoadError::UnknownAirport { line, code } => write!(f, "Line {}: unknown airport {}", line, code),
+            LoadError::DuplicateKey { line, key } => write!(f, "Line {}: duplicate key {}", line, key),
+            LoadError::UnsupportedFormat { path, reason } => write!(f, "Cannot read {}: {}", path, reason),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// Whether a bad row stops the load or is skipped and recorded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadMode {
+    Strict,
+    Lenient,
+}
+
+// What happened to the rows of one file: how many were read and kept, and why the rest were rejected
+#[derive(Debug)]
+pub struct LoadReport {
+    pub file: String,
+    pub rows: usize,
+    pub accepted: usize,
+    pub issues: Vec<LoadError>,
+    // (line, raw text) of the first rejected rows for each reason
+    pub samples: BTreeMap<&'static str, Vec<(usize, String)>>,
+}
+
+impl LoadReport {
+    pub fn new(file: &str) -> Self {
+        LoadReport { file: file.to_string(), rows: 0, accepted: 0, issues: vec![], samples: BTreeMap::new() }
+    }
+
+    // Fail on the issue in strict mode, otherwise record it along with the offending row and carry on
+    pub fn reject(&mut self, mode: LoadMode, issue: LoadError, text: &str) -> Result<(), LoadError> {
+        if mode == LoadMode::Strict {
+            return Err(issue);
+        }
+        let samples = self.samples.entry(issue.reason()).or_default();
+        if samples.len() < SAMPLES_PER_REASON {
+            samples.push((issue.line().unwrap_or(0), text.to_string()));
+        }
+        self.issues.push(issue);
+        Ok(())
+    }
+
+    // Add the issues of a later stage over the same file, e.g. building the graph from loaded routes;
+    // the rows accepted are those that made it through that stage
+    pub fn merge(&mut self, later: LoadReport) {
+        self.accepted = later.accepted;
+        for (reason, samples) in later.samples {
+            let kept = self.samples.entry(reason).or_default();
+            kept.extend(samples.into_iter().take(SAMPLES_PER_REASON.saturating_sub(kept.len())));
+        }
+        self.issues.extend(later.issues);
+    }
+
+    // Number of issues for each rejection reason
+    pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for issue in &self.issues {
+            *counts.entry(issue.reason()).or_default() += 1;
+        }
+        counts
+    }
+
+    // Unknown airport codes with the number of rows referencing each, most referenced first
+    pub fn unknown_airports(&self) -> Vec<(String, usize)> {
+        let mut references: HashMap<&String, usize> = HashMap::new();
+        for issue in &self.issues {
+            if let LoadError::UnknownAirport { code, .. } = issue {
+                *references.entry(code).or_default() += 1;
+            }
+        }
+        let mut ranked: Vec<(String, usize)> = references.into_iter().map(|(code, count)| (code.clone(), count)).collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    // A few lines for the terminal
+    pub fn summary(&self) -> String {
+        let counts: Vec<String> = self.counts().iter().map(|(reason, count)| format!("{}: {}", reason, count)).collect();
+        let mut summary = format!("{}: {} rows, {} accepted", self.file, self.rows, self.accepted);
+        if !counts.is_empty() {
+            summary.push_str(&format!(", {} issues ({})", self.issues.len(), counts.join(", ")));
+        }
+
+        let unknown = self.unknown_airports();
+        if !unknown.is_empty() {
+            let top: Vec<String> = unknown.iter().take(10).map(|(code, count)| format!("{} ({})", code, count)).collect();
+            summary.push_str(&format!("\n  {} unknown airports, most referenced: {}", unknown.len(), top.join(", ")));
+        }
+        for (reason, samples) in &self.samples {
+            for (line, text) in samples {
+                summary.push_str(&format!("\n  {} at line {}: {}", reason, line, text));
+            }
+        }
+        summary
+    }
+
+    pub fn to_json(&self) -> String {
+        let counts: Vec<String> = self.counts().iter().map(|(reason, count)| format!("{}:{}", json_string(reason), count)).collect();
+        let unknown: Vec<String> = self
+            .unknown_airports()
+            .iter()
+            .map(|(code, count)| format!("{{\"code\":{},\"routes\":{}}}", json_string(code), count))
+            .collect();
+        let samples: Vec<String> = self
+            .samples
+            .iter()
+            .flat_map(|(reason, samples)| {
+                samples.iter().map(move |(line, text)| {
+                    format!("{{\"reason\":{},\"line\":{},\"text\":{}}}", json_string(reason), line, json_string(text))
+                })
+            })
+            .collect();
+        format!(
+            "{{\"file\":{},\"rows\":{},\"accepted\":{},\"rejected\":{{{}}},\"unknown_airports\":[{}],\"samples\":[{}]}}",
+            json_string(&self.file),
+            self.rows,
+            self.accepted,
+            counts.join(","),
+            unknown.join(","),
+            samples.join(",")
+        )
+    }
+}
+
+// Loaded data along with what was skipped in lenient mode
+#[derive(Debug)]
+pub struct Loaded<T> {
+    pub data: T,
+    pub report: LoadReport,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_report() {
+        let mut report = LoadReport::new("routes.csv");
+        report.rows = 5;
+        report.accepted = 2;
+        for (line, code) in [(2, "XXX"), (3, "YYY"), (4, "XXX")] {
+            report.reject(LoadMode::Lenient, LoadError::UnknownAirport { line, code: code.to_string() }, &format!("BA,LHR,{}", code)).unwrap();
+        }
+        report.reject(LoadMode::Lenient, LoadError::MalformedRow { line: 5, reason: "too short".to_string() }, "0,BA").unwrap();
+
+        assert_eq!(report.counts().get("unknown airport"), Some(&3));
+        assert_eq!(report.unknown_airports(), vec![("XXX".to_string(), 2), ("YYY".to_string(), 1)]);
+        assert!(report.summary().contains("2 unknown airports, most referenced: XXX (2), YYY (1)"));
+        assert!(report.summary().contains("malformed row at line 5: 0,BA"));
+        assert!(report.to_json().starts_with("{\"file\":\"routes.csv\",\"rows\":5,\"accepted\":2,\"rejected\":{\"malformed row\":1,\"unknown airport\":3}"));
+
+        // Strict mode hands the issue back instead of recording it
+        assert!(report.reject(LoadMode::Strict, LoadError::DuplicateKey { line: 6, key: "LHR".to_string() }, "").is_err());
+        let error = report.reject(LoadMode::Strict, LoadError::MalformedRow { line: 7, reason: "no IATA or ICAO code".to_string() }, "").unwrap_err();
+        assert_eq!(error.to_string(), "Line 7: malformed row (no IATA or ICAO code)");
+        assert_eq!(report.issues.len(), 4);
+    }
+
+    #[test]
+    fn test_csv_error_message() {
+        let mut csv_reader = csv::ReaderBuilder::new().from_reader("code,name\nLHR,Heathrow,London\n".as_bytes());
+        let error = csv_reader.records().next().unwrap().unwrap_err();
+
+        let error = LoadError::from_csv("airports.csv", error);
+        assert!(matches!(error, LoadError::MalformedRow { line: 2, .. }));
+        assert!(error.to_string().contains("found record with 3 fields, but the previous record has 2 fields"), "{}", error);
+    }
+}
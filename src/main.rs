@@ -1,50 +1,241 @@
-mod airports;
-mod graph;
-mod bfs;
+use main_code::{aircraft, airlines, airport_checks, airports, bfs, community, critical, distance, export, format, geojson, graph, great_circle, ground, itinerary, kcore, kml, load, metrics, metro, mst, robustness, search, small_world, spatial, svg, validation};
+use airports::{load_airports_with_format, Airport};
+use graph::{adjacency_list_from_routes, count_routes, giant_component, load_routes_with_format, simple_graph, AdjacencyList, Route};
+use bfs::bfs;
+use metrics::network_metrics;
+use critical::{articulation_points, bridges};
+use robustness::{simulate_removal, write_degradation_csv, RemovalOrder};
+use community::detect_communities;
+use small_world::{local_clustering, small_world_comparison};
+use kcore::{core_decomposition, k_core};
+use mst::minimum_spanning_forest;
+use spatial::SpatialIndex;
+use itinerary::{plan_itinerary, Place};
+use metro::{apply_metro_areas, load_metro_areas_from_csv, metro_areas_from_cities, MetroMode};
+use ground::{add_ground_transfers, format_path, GroundTransfers};
+use geojson::{itinerary_geojson, network_geojson, write_geojson};
+use great_circle::leg_geometry;
+use svg::{render_svg, MapLayer, Projection};
+use export::{export_graph, to_dot, to_graphml};
+use kml::itinerary_kml;
+use distance::{Distance, DistanceModel, DistanceUnit};
+use search::{resolve_airport, search_airports};
+use load::{LoadMode, LoadReport};
+use format::{detect_format, InputFormat};
+use airlines::load_airlines_from_csv;
+use aircraft::load_aircraft_from_csv;
+use airport_checks::{check_airports_with_format, exclude_suspect_airports, AirportIssue, CHECK_NAMES};
+use validation::{read_route_records, validate_routes, write_validation_csv, RouteRule, RULE_NAMES};
 
-use airports::load_airports_from_csv;
-use graph::load_adjacency_list_from_csv;
-use bfs::{bfs, NodeWithDistanceAndPath}; // Import NodeWithDistanceAndPath here
-
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
+use std::str::FromStr;
 use rand::prelude::IteratorRandom; // Add this import
 
-fn main() -> Result<(), Box<dyn Error>> {
+// Errors are printed with their Display message, e.g. "Line 5862: malformed row (...)" for a LoadError in strict mode
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    // Flags may come before or after the command, e.g. `mainCode --units mi` or `mainCode --strict kcore`
+    let args = command_first(env::args().skip(1).collect());
+
+    // With --strict the first bad row aborts the load instead of being skipped
+    let mode = if has_flag(&args, "--strict") { LoadMode::Strict } else { LoadMode::Lenient };
+
+    let mut load_reports: Vec<LoadReport> = Vec::new();
+
+    // Data files default to this repo's CSVs; raw OpenFlights .dat files and OurAirports exports are detected from their
+    // first line, or named with --airports-format / --routes-format indexed|openflights|ourairports
+    let airports_file = option_value(&args, "--airports-file").unwrap_or("airports.csv");
+    let airports_format = input_format(&args, "--airports-format", airports_file)?;
+    let routes_file = option_value(&args, "--routes-file").unwrap_or("routes.csv");
+    let routes_format = input_format(&args, "--routes-format", routes_file)?;
+
     // Load location data from airports.csv
-    let airports = load_airports_from_csv("airports.csv")?;
+    let loaded = load_airports_with_format(airports_file, airports_format, mode)?;
+    load_reports.push(loaded.report);
+    let mut airports = loaded.data;
+
+    // Optionally drop airports failing the sanity checks before the network is built (--exclude-suspect-airports)
+    if has_flag(&args, "--exclude-suspect-airports") {
+        let issues = check_airports_with_format(airports_file, airports_format, &selected_checks(&args)?)?;
+        let excluded = exclude_suspect_airports(&mut airports, &issues);
+        eprintln!("Excluded {} suspect airports ({} issues)", excluded, issues.len());
+    }
 
     // Load adjacency list with connections from routes.csv using location data
-    let adjacency_list = load_adjacency_list_from_csv("routes.csv", &airports)?;
+    let loaded = load_routes_with_format(routes_file, routes_format, mode)?;
+    let mut routes_report = loaded.report;
+    let routes = loaded.data;
+    count_routes(&mut airports, &routes);
+    let distance_model: DistanceModel = parse_option(&args, "--distance-model", DistanceModel::Vincenty)?;
+    // Unit every report prints distances in (--units km|mi|nmi)
+    let units: DistanceUnit = parse_option(&args, "--units", DistanceUnit::Kilometers)?;
+    let loaded = adjacency_list_from_routes(&routes, &airports, distance_model, mode)?;
+    routes_report.merge(loaded.report);
+    load_reports.push(routes_report);
+    let mut adjacency_list = loaded.data;
 
+    // Optionally treat multi-airport cities as metro areas (--metro from airports.csv, grouping airports of a city
+    // within --metro-km KM of each other, or --metro-file mapping.csv)
+    let metro_areas = match option_value(&args, "--metro-file") {
+        Some(filename) => {
+            let loaded = load_metro_areas_from_csv(filename, mode)?;
+            load_reports.push(loaded.report);
+            Some(loaded.data)
+        }
+        None if has_flag(&args, "--metro") => Some(metro_areas_from_cities(&airports, parse_option(&args, "--metro-km", 100.0)?)),
+        None => None,
+    };
+    print_load_reports(&load_reports, option_value(&args, "--load-report").unwrap_or("summary"))?;
+    if let Some(metro_areas) = metro_areas {
+        let mode = match option_value(&args, "--metro-mode").unwrap_or("collapse") {
+            "collapse" => MetroMode::Collapse,
+            "link" => MetroMode::Link,
+            other => return Err(format!("Unknown metro mode: {} (expected collapse or link)", other).into()),
+        };
+        adjacency_list = apply_metro_areas(&adjacency_list, &mut airports, &metro_areas, mode);
+    }
+
+    // Optionally add surface transfers between nearby airports (--ground-km KM, --ground-penalty KM)
+    let ground_transfers = match option_value(&args, "--ground-km") {
+        Some(_) => {
+            let max_km: f64 = parse_option(&args, "--ground-km", 0.0)?;
+            let penalty_km: f64 = parse_option(&args, "--ground-penalty", 100.0)?;
+            add_ground_transfers(&mut adjacency_list, &airports, max_km, penalty_km)
+        }
+        None => GroundTransfers::new(),
+    };
+
+    // Without a command only flags remain
+    match args.first().map(String::as_str).filter(|arg| !arg.starts_with("--")) {
+        Some("metrics") => print_network_metrics(&adjacency_list, units, &args),
+        Some("critical") => print_critical_airports_and_routes(&adjacency_list),
+        Some("robustness") => write_robustness_curve(&adjacency_list, &args),
+        Some("communities") => print_communities(&adjacency_list, &airports),
+        Some("small-world") => print_small_world_comparison(&adjacency_list, &args),
+        Some("kcore") => print_core_decomposition(&adjacency_list),
+        Some("mst") => write_minimum_spanning_forest(&adjacency_list, units),
+        Some("search") => print_airport_search(&airports, &args),
+        Some("validate") => write_validated_routes(&airports, routes_file, routes_format, mode, &args),
+        Some("check-airports") => print_airport_issues(airports_file, airports_format, &args),
+        Some("nearest") | Some("within") => print_nearby_airports(&airports, units, &args),
+        Some("route") => print_itinerary(&adjacency_list, &airports, &ground_transfers, distance_model, units, &args),
+        Some("geojson") => write_geojson_file(&adjacency_list, &airports, &ground_transfers, distance_model, &args),
+        Some("svg") => write_svg_map(&adjacency_list, &airports, &routes, &ground_transfers, &args),
+        Some("dot") | Some("graphml") => write_graph_export(&adjacency_list, &airports, &routes, &args),
+        Some("kml") => write_itinerary_kml(&adjacency_list, &airports, &ground_transfers, &args),
+        Some(other) => Err(format!("Unknown command: {}", other).into()),
+        None => write_sampled_distances(&adjacency_list, units),
+    }
+}
+
+// What was rejected while loading the data files, on stderr: `--load-report summary|json|none`
+fn print_load_reports(reports: &[LoadReport], format: &str) -> Result<(), Box<dyn Error>> {
+    match format {
+        "summary" => reports.iter().for_each(|report| eprintln!("{}", report.summary())),
+        "json" => {
+            let reports: Vec<String> = reports.iter().map(LoadReport::to_json).collect();
+            eprintln!("[{}]", reports.join(","));
+        }
+        "none" => {}
+        other => return Err(format!("Unknown load report format: {} (expected summary, json or none)", other).into()),
+    }
+    Ok(())
+}
+
+// --flags that take no value; every other --flag is followed by one
+const SWITCHES: [&str; 3] = ["--strict", "--metro", "--exclude-suspect-airports"];
+
+// The command and its positional arguments, followed by the --flags and their values in their original order
+fn command_first(args: Vec<String>) -> Vec<String> {
+    let mut positional = Vec::new();
+    let mut flags = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            positional.push(arg);
+            continue;
+        }
+        let takes_value = !SWITCHES.contains(&arg.as_str());
+        flags.push(arg);
+        if takes_value {
+            flags.extend(args.next());
+        }
+    }
+    positional.extend(flags);
+    positional
+}
+
+// Value following a --flag on the command line
+fn option_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().position(|arg| arg == flag).and_then(|index| args.get(index + 1)).map(String::as_str)
+}
+
+// Whether a --flag without a value was given
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|arg| arg == flag)
+}
+
+// Parse the value of a --flag, falling back to the default when it is not given
+fn parse_option<T: FromStr>(args: &[String], flag: &str, default: T) -> Result<T, Box<dyn Error>> {
+    match option_value(args, flag) {
+        Some(value) => value.parse().map_err(|_| format!("Invalid value for {}: {}", flag, value).into()),
+        None => Ok(default),
+    }
+}
+
+// Format named by --flag, or detected from the first line of the file
+fn input_format(args: &[String], flag: &str, filename: &str) -> Result<InputFormat, Box<dyn Error>> {
+    match option_value(args, flag) {
+        Some(value) => Ok(value.parse()?),
+        None => Ok(detect_format(filename)?),
+    }
+}
+
+// Positional argument parsed as a number, e.g. a latitude or longitude
+fn parse_argument<T: FromStr>(args: &[String], index: usize, name: &str) -> Result<T, Box<dyn Error>> {
+    let value = args.get(index).ok_or_else(|| format!("Missing {}", name))?;
+    value.parse().map_err(|_| format!("Invalid {}: {}", name, value).into())
+}
+
+// Sample airports, write their distances and paths to output.txt along with the average distance
+fn write_sampled_distances(adjacency_list: &AdjacencyList, units: DistanceUnit) -> Result<(), Box<dyn Error>> {
     // Randomly sample x number of nodes for sampling
     let mut rng = rand::thread_rng();
-    let mut sampled_nodes: Vec<_> = adjacency_list.keys().choose_multiple(&mut rng, 1000);
+    let sampled_nodes: Vec<_> = adjacency_list.keys().choose_multiple(&mut rng, 1000);
 
     // Open output.txt to write results
     let mut output_file = File::create("output.txt")?;
 
     // Write the adjacency list to output.txt
-    for (airport, neighbors) in &adjacency_list {
-        writeln!(output_file, "Airport {}: {:?}", airport, neighbors)?;
+    for (airport, neighbors) in adjacency_list {
+        let neighbors: Vec<String> = neighbors.iter().map(|(neighbor, distance)| format!("{} ({})", neighbor, units.format(*distance))).collect();
+        writeln!(output_file, "Airport {}: {}", airport, neighbors.join(", "))?;
     }
 
-    let mut total_distance = 0.0;
+    let mut total_distance = Distance::ZERO;
     let mut pair_count = 0;
 
     // Calculate distances from the sampled nodes to all other airports
     for sampled_node in sampled_nodes.iter() {
-        let distances = bfs(&adjacency_list, *sampled_node);
+        let distances = bfs(adjacency_list, sampled_node);
 
         // Write distances and paths from the sampled node to all other airports to output.txt
         for (airport, node_with_distance_path) in &distances {
-            writeln!(output_file, "Distance from {} to {}: {:.2} kilometers", sampled_node, airport, node_with_distance_path.distance)?;
+            writeln!(output_file, "Distance from {} to {}: {}", sampled_node, airport, units.format(node_with_distance_path.distance))?;
             writeln!(output_file, "Path: {:?}", node_with_distance_path.path)?;
 
             // Skip nodes with infinite distance
-            if node_with_distance_path.distance != std::f64::INFINITY {
+            if node_with_distance_path.distance.is_finite() {
                 // Add up the distances between each pair
                 total_distance += node_with_distance_path.distance;
                 pair_count += 1;
@@ -54,80 +245,471 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Calculate the average distance
     let average_distance = if pair_count > 0 {
-        total_distance / pair_count as f64
+        Distance::from_km(total_distance.km() / pair_count as f64)
     } else {
-        0.0
+        Distance::ZERO
     };
 
     // Output the average distance
-    writeln!(output_file, "\nAverage distance between every reachable airport within sampled pairs: {:.2} kilometers", average_distance)?;
+    writeln!(output_file, "\nAverage distance between every reachable airport within sampled pairs: {}", units.format(average_distance))?;
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::{HashMap, HashSet};
+// Print diameter, radius and center of the giant component, and write every eccentricity to eccentricity.csv
+// With --core K the metrics are computed on the backbone k-core instead of the full network
+fn print_network_metrics(adjacency_list: &AdjacencyList, units: DistanceUnit, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let giant = match option_value(args, "--core") {
+        Some(_) => giant_component(&k_core(adjacency_list, parse_option(args, "--core", 0)?)),
+        None => giant_component(adjacency_list),
+    };
+    let metrics = network_metrics(&giant);
 
-    #[test]
-    fn test_bfs() {
-        // Sample dataset for testing
-        let mut adjacency_list: HashMap<String, Vec<(String, f64)>> = HashMap::new();
-        adjacency_list.insert("A".to_string(), vec![("B".to_string(), 1.0)]);
-        adjacency_list.insert("B".to_string(), vec![("C".to_string(), 1.0)]);
-        adjacency_list.insert("C".to_string(), vec![("D".to_string(), 1.0)]);
-
-        // Run BFS from node "A"
-        let distances = bfs(&adjacency_list, "A");
-
-        // Expected distances and paths
-        let expected_distances: HashMap<String, NodeWithDistanceAndPath> = [
-            ("A".to_string(), NodeWithDistanceAndPath { distance: 0.0, path: vec!["A".to_string()] }),
-            ("B".to_string(), NodeWithDistanceAndPath { distance: 1.0, path: vec!["A".to_string(), "B".to_string()] }),
-            ("C".to_string(), NodeWithDistanceAndPath { distance: 2.0, path: vec!["A".to_string(), "B".to_string(), "C".to_string()] }),
-            ("D".to_string(), NodeWithDistanceAndPath { distance: 3.0, path: vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()] }),
-        ].iter().cloned().collect();
-
-        // Check if the actual distances match the expected ones
-        for (node, expected_node) in expected_distances.iter() {
-            assert_eq!(distances.get(node), Some(expected_node));
+    println!("Giant component: {} of {} airports", giant.len(), adjacency_list.len());
+    println!("Diameter: {} flights via {:?}", metrics.hop_diameter, metrics.hop_diameter_path);
+    println!("Diameter: {} via {:?}", units.format(metrics.km_diameter), metrics.km_diameter_path);
+    println!("Radius: {} flights", metrics.radius);
+    println!("Center airports: {:?}", metrics.center);
+
+    let mut eccentricities: Vec<_> = metrics.eccentricity.iter().collect();
+    eccentricities.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut output_file = File::create("eccentricity.csv")?;
+    writeln!(output_file, "airport,eccentricity")?;
+    for (airport, eccentricity) in eccentricities {
+        writeln!(output_file, "{},{}", airport, eccentricity)?;
+    }
+
+    Ok(())
+}
+
+// Print the airports and routes whose closure would disconnect part of the network
+fn print_critical_airports_and_routes(adjacency_list: &AdjacencyList) -> Result<(), Box<dyn Error>> {
+    let critical_airports = articulation_points(adjacency_list);
+    println!("{} critical airports:", critical_airports.len());
+    for critical in &critical_airports {
+        println!("Closing {} cuts off {} airports: {:?}", critical.airport, critical.cut_off.len(), critical.cut_off);
+    }
+
+    let critical_routes = bridges(adjacency_list);
+    println!("\n{} critical routes:", critical_routes.len());
+    for critical in &critical_routes {
+        println!("Closing {} - {} cuts off {} airports: {:?}", critical.from, critical.to, critical.cut_off.len(), critical.cut_off);
+    }
+
+    Ok(())
+}
+
+// Remove airports in the chosen order and write the degradation curve to a CSV file
+fn write_robustness_curve(adjacency_list: &AdjacencyList, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let seed: u64 = parse_option(args, "--seed", 42)?;
+    let order = match option_value(args, "--order").unwrap_or("random") {
+        "random" => RemovalOrder::Random(seed),
+        "degree" => RemovalOrder::Degree,
+        "betweenness" => RemovalOrder::Betweenness,
+        other => return Err(format!("Unknown removal order: {} (expected random, degree or betweenness)", other).into()),
+    };
+    let step: usize = parse_option(args, "--step", 10)?;
+    let path_samples: usize = parse_option(args, "--samples", 50)?;
+    let output = option_value(args, "--output").unwrap_or("robustness.csv");
+
+    let steps = simulate_removal(adjacency_list, order, step, path_samples);
+    write_degradation_csv(output, &steps)?;
+    println!("Wrote {} steps of {:?} removal to {}", steps.len(), order, output);
+
+    Ok(())
+}
+
+// Print the regional communities found by Louvain with their dominant countries
+fn print_communities(adjacency_list: &AdjacencyList, airports: &HashMap<String, Airport>) -> Result<(), Box<dyn Error>> {
+    let detection = detect_communities(adjacency_list, airports);
+    println!("{} communities, modularity {:.4}", detection.communities.len(), detection.modularity);
+
+    for (index, community) in detection.communities.iter().enumerate() {
+        let countries: Vec<String> = community.countries.iter().take(3).map(|(country, count)| format!("{} ({})", country, count)).collect();
+        println!("\nCommunity {}: {} airports, mostly {}", index + 1, community.airports.len(), countries.join(", "));
+        println!("Airports: {:?}", community.airports);
+    }
+
+    Ok(())
+}
+
+// Print clustering and path length against degree-preserving random graphs, and write local clustering to clustering.csv
+fn print_small_world_comparison(adjacency_list: &AdjacencyList, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let seed: u64 = parse_option(args, "--seed", 42)?;
+    let random_graphs: usize = parse_option(args, "--graphs", 5)?;
+    let path_samples: usize = parse_option(args, "--samples", 200)?;
+
+    let comparison = small_world_comparison(adjacency_list, random_graphs, path_samples, seed);
+    println!("{:<24}{:>12}{:>12}", "", "observed", "random");
+    println!("{:<24}{:>12.4}{:>12.4}", "Average clustering", comparison.observed_clustering, comparison.random_clustering);
+    println!("{:<24}{:>12.4}{:>12.4}", "Transitivity", comparison.observed_transitivity, comparison.random_transitivity);
+    println!("{:<24}{:>12.4}{:>12.4}", "Average path length", comparison.observed_path_length, comparison.random_path_length);
+    let sigma = comparison.sigma.map(|sigma| format!("{:.2}", sigma)).unwrap_or_else(|| "n/a".to_string());
+    println!("Small-world sigma: {} ({} random graphs, seed {})", sigma, random_graphs, seed);
+
+    let mut clustering: Vec<_> = local_clustering(adjacency_list).into_iter().collect();
+    clustering.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut output_file = File::create("clustering.csv")?;
+    writeln!(output_file, "airport,clustering")?;
+    for (airport, coefficient) in clustering {
+        writeln!(output_file, "{},{:.4}", airport, coefficient)?;
+    }
+
+    Ok(())
+}
+
+// Print the innermost k-core and the size of every shell
+fn print_core_decomposition(adjacency_list: &AdjacencyList) -> Result<(), Box<dyn Error>> {
+    let decomposition = core_decomposition(adjacency_list);
+    println!("Innermost core: k = {} with {} airports", decomposition.innermost_core, decomposition.innermost.len());
+    println!("Airports: {:?}", decomposition.innermost);
+
+    println!("\nShell sizes:");
+    for (k, size) in &decomposition.shell_sizes {
+        println!("k = {}: {} airports", k, size);
+    }
+
+    Ok(())
+}
+
+// Print the minimum spanning forest summary and write the routes it keeps to mst.csv
+fn write_minimum_spanning_forest(adjacency_list: &AdjacencyList, units: DistanceUnit) -> Result<(), Box<dyn Error>> {
+    let forest = minimum_spanning_forest(adjacency_list);
+    let route_count: usize = forest.trees.iter().map(|tree| tree.edges.len()).sum();
+    println!("Minimum spanning forest: {} trees, {} routes, {}", forest.trees.len(), route_count, units.format(forest.total_length));
+
+    let mut output_file = File::create("mst.csv")?;
+    writeln!(output_file, "tree,from,to,distance_{}", units.abbreviation())?;
+    for (index, tree) in forest.trees.iter().enumerate() {
+        println!("Tree {}: {} airports, {}", index + 1, tree.airports.len(), units.format(tree.length));
+        for (from, to, distance) in &tree.edges {
+            writeln!(output_file, "{},{},{},{:.2}", index + 1, from, to, distance.in_units(units))?;
         }
     }
 
-    #[test]
-    fn test_bfs_unreachable_airports() {
-        // Sample dataset for testing with unreachable airports
-        let mut adjacency_list: HashMap<String, Vec<(String, f64)>> = HashMap::new();
-        adjacency_list.insert("A".to_string(), vec![("B".to_string(), 1.0)]);
-        adjacency_list.insert("B".to_string(), vec![("C".to_string(), 1.0)]);
-        adjacency_list.insert("C".to_string(), vec![("D".to_string(), 1.0)]);
-        adjacency_list.insert("E".to_string(), vec![("F".to_string(), 1.0)]);
-
-        // Run BFS from node "A"
-        let distances = bfs(&adjacency_list, "A");
-
-        // Expected distances and paths
-        let expected_distances: HashMap<String, NodeWithDistanceAndPath> = [
-            ("A".to_string(), NodeWithDistanceAndPath { distance: 0.0, path: vec!["A".to_string()] }),
-            ("B".to_string(), NodeWithDistanceAndPath { distance: 1.0, path: vec!["A".to_string(), "B".to_string()] }),
-            ("C".to_string(), NodeWithDistanceAndPath { distance: 2.0, path: vec!["A".to_string(), "B".to_string(), "C".to_string()] }),
-            ("D".to_string(), NodeWithDistanceAndPath { distance: 3.0, path: vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()] }),
-        ].iter().cloned().collect();
-
-        // Check if the actual distances match the expected ones
-        for (node, expected_node) in expected_distances.iter() {
-            if let Some(actual_node) = distances.get(node) {
-                // Check distance
-                assert_eq!(actual_node.distance, expected_node.distance, "Distance mismatch for node {}", node);
-                // Check path
-                assert_eq!(actual_node.path, expected_node.path, "Path mismatch for node {}", node);
-            } else {
-                // Node not found in actual distances, assert that it's unreachable
-                assert!(!adjacency_list.contains_key(node), "Node {} should not be reachable", node);
+    Ok(())
+}
+
+// Checks named by --checks a,b,..., or all of them
+fn selected_checks(args: &[String]) -> Result<Vec<&'static str>, Box<dyn Error>> {
+    let Some(list) = option_value(args, "--checks") else {
+        return Ok(CHECK_NAMES.to_vec());
+    };
+    list.split(',')
+        .map(str::trim)
+        .map(|name| {
+            CHECK_NAMES
+                .iter()
+                .find(|check| **check == name)
+                .copied()
+                .ok_or_else(|| format!("Unknown check: {} (expected any of {})", name, CHECK_NAMES.join(", ")).into())
+        })
+        .collect()
+}
+
+// `check-airports [--checks a,b,...]`: list the suspicious rows of the airports file with their line numbers
+fn print_airport_issues(filename: &str, format: InputFormat, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let issues: Vec<AirportIssue> = check_airports_with_format(filename, format, &selected_checks(args)?)?;
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for issue in &issues {
+        println!("line {} ({}): {}: {}", issue.line, issue.code, issue.check, issue.detail);
+        *counts.entry(issue.check).or_default() += 1;
+    }
+
+    println!("{} issues", issues.len());
+    for check in CHECK_NAMES {
+        if let Some(count) = counts.get(check) {
+            println!("  {}: {}", check, count);
+        }
+    }
+
+    Ok(())
+}
+
+// `validate [--rules a,b,...] [--clean FILE] [--rejects FILE] [--airlines FILE] [--airplanes FILE]`:
+// split the routes file (--routes-file, in any route format) into the rows passing every rule and the rejected rows annotated with the rules they fail
+fn write_validated_routes(
+    airports: &HashMap<String, Airport>,
+    routes_file: &str,
+    routes_format: InputFormat,
+    mode: LoadMode,
+    args: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let selected: Vec<&str> = match option_value(args, "--rules") {
+        Some(list) => list.split(',').map(str::trim).collect(),
+        None => RULE_NAMES.to_vec(),
+    };
+    if let Some(unknown) = selected.iter().find(|name| !RULE_NAMES.contains(name)) {
+        return Err(format!("Unknown rule: {} (expected any of {})", unknown, RULE_NAMES.join(", ")).into());
+    }
+    let clean_file = option_value(args, "--clean").unwrap_or("valid_routes.csv");
+    let rejects_file = option_value(args, "--rejects").unwrap_or("rejected_routes.csv");
+
+    // Reference data is only read for the rules that need it
+    let airlines = if selected.contains(&"inactive-airline") {
+        load_airlines_from_csv(option_value(args, "--airlines").unwrap_or("airlines.csv"), mode)?.data
+    } else {
+        HashMap::new()
+    };
+    let aircraft = if selected.contains(&"unknown-equipment") {
+        load_aircraft_from_csv(option_value(args, "--airplanes").unwrap_or("airplanes.csv"), mode)?.data
+    } else {
+        HashMap::new()
+    };
+
+    let mut rules: Vec<Box<dyn RouteRule>> = Vec::new();
+    for name in RULE_NAMES.iter().filter(|name| selected.contains(name)) {
+        rules.push(match *name {
+            "unknown-airport" => Box::new(validation::UnknownAirports { airports }),
+            "self-loop" => Box::new(validation::SelfLoops),
+            "duplicate" => Box::new(validation::DuplicateRows::default()),
+            "inactive-airline" => Box::new(validation::InactiveAirlines { airlines: &airlines }),
+            "unknown-equipment" => Box::new(validation::UnknownEquipment { aircraft: &aircraft }),
+            _ => Box::new(validation::ImplausibleLegs { airports }),
+        });
+    }
+
+    let (header, records) = read_route_records(routes_file, routes_format)?;
+    let result = validate_routes(header, records, &mut rules);
+    write_validation_csv(&result, clean_file, rejects_file)?;
+
+    println!("{} clean routes written to {}, {} rejected written to {}", result.clean.len(), clean_file, result.rejected.len(), rejects_file);
+    for (rule, count) in result.failures_by_rule() {
+        println!("  {}: {}", rule, count);
+    }
+
+    Ok(())
+}
+
+// `search QUERY [--count N]`: airports matching a code, name, city or country, allowing for typos
+fn print_airport_search(airports: &HashMap<String, Airport>, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let query = args.get(1).ok_or("Missing search query")?;
+    let candidates = search_airports(airports, query, parse_option(args, "--count", 10)?);
+    if candidates.is_empty() {
+        println!("No airports match {:?}", query);
+    }
+    for candidate in candidates {
+        let airport = &airports[&candidate.code];
+        println!("{} {}: {}, {}, {} (matched {:?})", candidate.code, airport.icao, airport.name, airport.city, airport.country, candidate.kind);
+    }
+
+    Ok(())
+}
+
+// `nearest LAT LON [--count N]` or `within LAT LON KM`: airports around a coordinate
+fn print_nearby_airports(airports: &HashMap<String, Airport>, units: DistanceUnit, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let latitude: f64 = parse_argument(args, 1, "latitude")?;
+    let longitude: f64 = parse_argument(args, 2, "longitude")?;
+    let index = SpatialIndex::new(airports);
+
+    let found = if args[0] == "nearest" {
+        index.nearest(latitude, longitude, parse_option(args, "--count", 5)?)
+    } else {
+        index.within(latitude, longitude, parse_argument(args, 3, "radius in kilometers")?)
+    };
+
+    for (code, distance) in found {
+        println!("{}: {} ({})", code, units.format(Distance::from_km(distance)), airports[&code].country);
+    }
+
+    Ok(())
+}
+
+// `route FROM TO [--radius KM] [--waypoints N]`: best itinerary between airport codes, "lat,lon" pairs or city names
+fn print_itinerary(
+    adjacency_list: &AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    ground_transfers: &GroundTransfers,
+    model: DistanceModel,
+    units: DistanceUnit,
+    args: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let origin = Place::parse(args.get(1).ok_or("Missing origin")?, airports)?;
+    let destination = Place::parse(args.get(2).ok_or("Missing destination")?, airports)?;
+    let radius_km: f64 = parse_option(args, "--radius", 100.0)?;
+    let index = SpatialIndex::new(airports);
+
+    match plan_itinerary(adjacency_list, airports, &index, &origin, &destination, ground_transfers, radius_km)? {
+        Some(itinerary) => {
+            println!("Ground to {}: {}", itinerary.origin_airport, units.format(itinerary.ground_to_origin));
+            println!("Route: {}, {}", format_path(&itinerary.path, ground_transfers), units.format(itinerary.flight));
+            if itinerary.ground_transfers > Distance::ZERO {
+                println!("Ground transfers: {} (search penalty {})", units.format(itinerary.ground_transfers), units.format(itinerary.transfer_penalty));
             }
+            let waypoints: usize = parse_option(args, "--waypoints", 0)?;
+            for pair in itinerary.path.windows(2) {
+                if let Some(transfer) = ground_transfers.get(&(pair[0].clone(), pair[1].clone())) {
+                    println!("  {} -[ground]-> {}: {} by ground", pair[0], pair[1], units.format(transfer.distance));
+                    continue;
+                }
+                let leg = leg_geometry(&airports[&pair[0]].location, &airports[&pair[1]].location, waypoints.max(1), model);
+                println!(
+                    "  {} -> {}: {}, bearing {:.1}° out / {:.1}° in, latitude {:.2}° to {:.2}°{}{}",
+                    pair[0],
+                    pair[1],
+                    units.format(Distance::from_km(leg.distance_km)),
+                    leg.initial_bearing,
+                    leg.final_bearing,
+                    leg.min_latitude,
+                    leg.max_latitude,
+                    if leg.crosses_equator { ", crosses the equator" } else { "" },
+                    if leg.crosses_antimeridian { ", crosses the antimeridian" } else { "" },
+                );
+                if waypoints > 0 {
+                    let points: Vec<String> = leg.waypoints.iter().map(|(lat, lon)| format!("({:.3}, {:.3})", lat, lon)).collect();
+                    println!("    Waypoints: {}", points.join(" "));
+                }
+            }
+            println!("Ground from {}: {}", itinerary.destination_airport, units.format(itinerary.ground_from_destination));
+            println!("Total: {}", units.format(itinerary.total()));
         }
+        None => println!("No itinerary found between {:?} and {:?}", origin, destination),
     }
 
+    Ok(())
+}
+
+// `geojson OUTPUT [--from PLACE --to PLACE]`: the whole network, or one itinerary, as GeoJSON
+fn write_geojson_file(
+    adjacency_list: &AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    ground_transfers: &GroundTransfers,
+    model: DistanceModel,
+    args: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let output = args.get(1).ok_or("Missing output file")?;
+
+    let contents = match (option_value(args, "--from"), option_value(args, "--to")) {
+        (Some(from), Some(to)) => {
+            let origin = Place::parse(from, airports)?;
+            let destination = Place::parse(to, airports)?;
+            let radius_km: f64 = parse_option(args, "--radius", 100.0)?;
+            let index = SpatialIndex::new(airports);
+            let itinerary = plan_itinerary(adjacency_list, airports, &index, &origin, &destination, ground_transfers, radius_km)?
+                .ok_or_else(|| format!("No itinerary found between {} and {}", from, to))?;
+            itinerary_geojson(&itinerary, airports, ground_transfers, model)
+        }
+        _ => network_geojson(adjacency_list, airports),
+    };
+
+    write_geojson(output, &contents)?;
+    println!("Wrote {}", output);
+    Ok(())
+}
+
+// `svg OUTPUT [--projection equirectangular|robinson] [--width PX] [--airport CODE | --airline CODE | --from PLACE --to PLACE]`
+fn write_svg_map(
+    adjacency_list: &AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    routes: &[Route],
+    ground_transfers: &GroundTransfers,
+    args: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let output = args.get(1).ok_or("Missing output file")?;
+    let projection = match option_value(args, "--projection").unwrap_or("robinson") {
+        "equirectangular" => Projection::Equirectangular,
+        "robinson" => Projection::Robinson,
+        other => return Err(format!("Unknown projection: {} (expected equirectangular or robinson)", other).into()),
+    };
+    let width: f64 = parse_option(args, "--width", 2000.0)?;
+
+    let simple = simple_graph(adjacency_list);
+    let degrees: HashMap<String, usize> = simple.iter().map(|(code, neighbors)| (code.clone(), neighbors.len())).collect();
+
+    // Undirected airport pairs to draw, each listed once
+    let pairs: BTreeSet<(String, String)> = if let Some(code) = option_value(args, "--airport") {
+        let code = resolve_airport(airports, code)?;
+        let neighbors = simple.get(&code).ok_or_else(|| format!("Airport {} has no routes", code))?;
+        neighbors.iter().map(|(neighbor, _)| (code.clone(), neighbor.clone())).collect()
+    } else if let Some(airline) = option_value(args, "--airline") {
+        routes
+            .iter()
+            .filter(|route| route.airline == airline)
+            .map(|route| (route.from.clone().min(route.to.clone()), route.from.clone().max(route.to.clone())))
+            .collect()
+    } else if let (Some(from), Some(to)) = (option_value(args, "--from"), option_value(args, "--to")) {
+        let index = SpatialIndex::new(airports);
+        let radius_km: f64 = parse_option(args, "--radius", 100.0)?;
+        let itinerary = plan_itinerary(adjacency_list, airports, &index, &Place::parse(from, airports)?, &Place::parse(to, airports)?, ground_transfers, radius_km)?
+            .ok_or_else(|| format!("No itinerary found between {} and {}", from, to))?;
+        itinerary.path.windows(2).map(|pair| (pair[0].clone(), pair[1].clone())).collect()
+    } else {
+        simple
+            .iter()
+            .flat_map(|(code, neighbors)| neighbors.iter().filter(move |(neighbor, _)| code < neighbor).map(move |(neighbor, _)| (code.clone(), neighbor.clone())))
+            .collect()
+    };
+
+    let layer = MapLayer { routes: pairs.into_iter().collect(), degrees: &degrees };
+    let mut output_file = File::create(output)?;
+    output_file.write_all(render_svg(airports, &layer, projection, width).as_bytes())?;
+    println!("Wrote {} routes to {}", layer.routes.len(), output);
+
+    Ok(())
+}
+
+// `dot OUTPUT` or `graphml OUTPUT`, optionally filtered with --airport CODE (and its destinations), --country NAME or --core K
+fn write_graph_export(adjacency_list: &AdjacencyList, airports: &HashMap<String, Airport>, routes: &[Route], args: &[String]) -> Result<(), Box<dyn Error>> {
+    let output = args.get(1).ok_or("Missing output file")?;
+
+    let filter: Option<HashSet<String>> = if let Some(code) = option_value(args, "--airport") {
+        let code = resolve_airport(airports, code)?;
+        let neighbors = adjacency_list.get(&code).ok_or_else(|| format!("Airport {} has no routes", code))?;
+        Some(neighbors.iter().map(|(neighbor, _)| neighbor.clone()).chain([code]).collect())
+    } else if let Some(country) = option_value(args, "--country") {
+        Some(adjacency_list.keys().filter(|code| airports.get(*code).is_some_and(|a| a.country == country)).cloned().collect())
+    } else if option_value(args, "--core").is_some() {
+        Some(k_core(adjacency_list, parse_option(args, "--core", 0)?).into_keys().collect())
+    } else {
+        None
+    };
+
+    let graph = export_graph(adjacency_list, routes, filter.as_ref());
+    let contents = if args[0] == "dot" { to_dot(&graph, airports) } else { to_graphml(&graph, airports) };
+
+    let mut output_file = File::create(output)?;
+    output_file.write_all(contents.as_bytes())?;
+    println!("Wrote {} airports and {} routes to {}", graph.airports.len(), graph.edges.len(), output);
+
+    Ok(())
+}
+
+// `kml OUTPUT --from PLACE --to PLACE [--radius KM]`: an itinerary for Google Earth-like viewers
+fn write_itinerary_kml(
+    adjacency_list: &AdjacencyList,
+    airports: &HashMap<String, Airport>,
+    ground_transfers: &GroundTransfers,
+    args: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let output = args.get(1).ok_or("Missing output file")?;
+    let from = option_value(args, "--from").ok_or("Missing --from")?;
+    let to = option_value(args, "--to").ok_or("Missing --to")?;
+    let radius_km: f64 = parse_option(args, "--radius", 100.0)?;
 
+    let index = SpatialIndex::new(airports);
+    let itinerary = plan_itinerary(adjacency_list, airports, &index, &Place::parse(from, airports)?, &Place::parse(to, airports)?, ground_transfers, radius_km)?
+        .ok_or_else(|| format!("No itinerary found between {} and {}", from, to))?;
+
+    let mut output_file = File::create(output)?;
+    output_file.write_all(itinerary_kml(&itinerary, airports, ground_transfers).as_bytes())?;
+    println!("Wrote {}", output);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_before_command() {
+        let args = |text: &str| text.split(' ').map(String::from).collect::<Vec<String>>();
+        assert_eq!(command_first(args("--units mi --strict kcore")), args("kcore --units mi --strict"));
+        assert_eq!(command_first(args("route --metro LHR --units nmi JFK")), args("route LHR JFK --metro --units nmi"));
+        // Without a command the flags stay as they are
+        assert_eq!(command_first(args("--units mi")), args("--units mi"));
+        assert_eq!(option_value(&command_first(args("--strict --units km")), "--units"), Some("km"));
+    }
 }
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use crate::bfs::{hop_counts, reconstruct_path, shortest_path_tree};
+use crate::distance::Distance;
+use crate::graph::AdjacencyList;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkMetrics {
+    pub hop_diameter: usize,
+    pub hop_diameter_path: Vec<String>,
+    pub km_diameter: Distance,
+    pub km_diameter_path: Vec<String>,
+    pub eccentricity: HashMap<String, usize>,
+    pub radius: usize,
+    pub center: Vec<String>,
+}
+
+// Rebuild one fewest-hops path by stepping back through airports one hop closer to the source
+fn hop_path(adjacency_list: &AdjacencyList, hops: &HashMap<String, usize>, target: &str) -> Vec<String> {
+    let mut path = vec![target.to_string()];
+    let mut current = target.to_string();
+    while hops[&current] > 0 {
+        let level = hops[&current] - 1;
+        let previous = adjacency_list[&current]
+            .iter()
+            .map(|(neighbor, _)| neighbor)
+            .filter(|neighbor| hops.get(*neighbor) == Some(&level))
+            .min()
+            .unwrap()
+            .clone();
+        path.push(previous.clone());
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+// Diameter, eccentricity, radius and center of a connected graph (usually the giant component)
+pub fn network_metrics(adjacency_list: &AdjacencyList) -> NetworkMetrics {
+    let mut airports: Vec<&String> = adjacency_list.keys().collect();
+    airports.sort();
+
+    let mut eccentricity: HashMap<String, usize> = HashMap::new();
+    let mut hop_extreme: Option<(&String, String, usize)> = None;
+    let mut km_extreme: Option<(&String, String, Distance)> = None;
+
+    for &airport in &airports {
+        // Farthest airport by number of flights
+        let hops = hop_counts(adjacency_list, airport);
+        let (far, &max_hops) = hops.iter().max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0))).unwrap();
+        eccentricity.insert(airport.clone(), max_hops);
+        if hop_extreme.as_ref().is_none_or(|(_, _, best)| max_hops > *best) {
+            hop_extreme = Some((airport, far.clone(), max_hops));
+        }
+
+        // Farthest airport by flown distance along the shortest route
+        let (distances, _) = shortest_path_tree(adjacency_list, airport);
+        let (far, &max_km) = distances.iter().max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0))).unwrap();
+        if km_extreme.as_ref().is_none_or(|(_, _, best)| max_km > *best) {
+            km_extreme = Some((airport, far.clone(), max_km));
+        }
+    }
+
+    let (hop_diameter, hop_diameter_path) = match hop_extreme {
+        Some((source, target, diameter)) => {
+            let hops = hop_counts(adjacency_list, source);
+            (diameter, hop_path(adjacency_list, &hops, &target))
+        }
+        None => (0, vec![]),
+    };
+
+    let (km_diameter, km_diameter_path) = match km_extreme {
+        Some((source, target, diameter)) => {
+            let (_, previous) = shortest_path_tree(adjacency_list, source);
+            (diameter, reconstruct_path(&previous, source, &target))
+        }
+        None => (Distance::ZERO, vec![]),
+    };
+
+    let radius = eccentricity.values().copied().min().unwrap_or(0);
+    let mut center: Vec<String> = eccentricity
+        .iter()
+        .filter(|(_, &ecc)| ecc == radius)
+        .map(|(airport, _)| airport.clone())
+        .collect();
+    center.sort();
+
+    NetworkMetrics { hop_diameter, hop_diameter_path, km_diameter, km_diameter_path, eccentricity, radius, center }
+}
+
+// Average number of flights between airports of the giant component, estimated from sampled sources
+pub fn average_path_length(giant: &AdjacencyList, path_samples: usize, rng: &mut StdRng) -> f64 {
+    let mut airports: Vec<&String> = giant.keys().collect();
+    airports.sort();
+    let sources = airports.into_iter().choose_multiple(rng, path_samples);
+
+    let mut total_hops = 0;
+    let mut pair_count = 0;
+    for source in sources {
+        let hops = hop_counts(giant, source);
+        total_hops += hops.values().sum::<usize>();
+        pair_count += hops.len() - 1;
+    }
+
+    if pair_count > 0 {
+        total_hops as f64 / pair_count as f64
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_network_metrics() {
+        // Path A - B - C - D with a long direct shortcut from A to D
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("A".to_string(), vec![("B".to_string(), Distance::from_km(1.0)), ("D".to_string(), Distance::from_km(10.0))]);
+        adjacency_list.insert("B".to_string(), vec![("A".to_string(), Distance::from_km(1.0)), ("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("C".to_string(), vec![("B".to_string(), Distance::from_km(1.0)), ("D".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("D".to_string(), vec![("C".to_string(), Distance::from_km(1.0)), ("A".to_string(), Distance::from_km(10.0))]);
+
+        let metrics = network_metrics(&adjacency_list);
+
+        assert_eq!(metrics.hop_diameter, 2);
+        assert_eq!(metrics.radius, 2);
+        assert_eq!(metrics.center, vec!["A", "B", "C", "D"]);
+        assert_eq!(metrics.km_diameter, Distance::from_km(3.0));
+        assert_eq!(metrics.km_diameter_path, vec!["A", "B", "C", "D"]);
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, HashMap};
+use geoutils::Location;
+use crate::airports::Airport;
+use crate::distance::{haversine_km, Distance};
+use crate::graph::AdjacencyList;
+use crate::load::{LoadError, LoadMode, LoadReport, Loaded};
+
+// Airport code -> name of the metro area it belongs to
+pub type MetroAreas = HashMap<String, String>;
+
+// How co-located airports are combined in the graph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetroMode {
+    // Replace the member airports with a single node named after the metro area
+    Collapse,
+    // Keep the airports but connect every pair of members with a zero-kilometer link
+    Link,
+}
+
+// Group airports sharing a City and Country in airports.csv, named "City, Country". Only airports within
+// max_km of another member are grouped, so that far-apart cities of the same name (Portland, Oregon and
+// Portland, Maine) stay separate; when a name splits into several groups each is suffixed with its first code
+pub fn metro_areas_from_cities(airports: &HashMap<String, Airport>, max_km: f64) -> MetroAreas {
+    let mut cities: HashMap<(String, String), Vec<&String>> = HashMap::new();
+    for (code, airport) in airports {
+        if !airport.city.is_empty() {
+            cities.entry((airport.city.clone(), airport.country.clone())).or_default().push(code);
+        }
+    }
+
+    let mut metro_areas: MetroAreas = HashMap::new();
+    for ((city, country), mut codes) in cities {
+        codes.sort();
+        let clusters: Vec<Vec<&String>> = clusters_within(codes, airports, max_km).into_iter().filter(|cluster| cluster.len() > 1).collect();
+        for cluster in &clusters {
+            let name = if clusters.len() == 1 { format!("{}, {}", city, country) } else { format!("{}, {} ({})", city, country, cluster[0]) };
+            for code in cluster {
+                metro_areas.insert((*code).clone(), name.clone());
+            }
+        }
+    }
+    metro_areas
+}
+
+// Split airports into groups where each member is within max_km of another member of its group
+fn clusters_within<'a>(codes: Vec<&'a String>, airports: &HashMap<String, Airport>, max_km: f64) -> Vec<Vec<&'a String>> {
+    let mut clusters: Vec<Vec<&String>> = Vec::new();
+    for code in codes {
+        let location = &airports[code].location;
+        let (near, mut far): (Vec<Vec<&String>>, Vec<Vec<&String>>) = clusters
+            .into_iter()
+            .partition(|cluster| cluster.iter().any(|member| haversine_km(location, &airports[*member].location) <= max_km));
+        let mut merged: Vec<&String> = near.into_iter().flatten().collect();
+        merged.push(code);
+        merged.sort();
+        far.push(merged);
+        clusters = far;
+    }
+    clusters.sort();
+    clusters
+}
+
+// Read a user-supplied mapping with "airport,metro" columns, e.g. "LHR,LON"
+pub fn load_metro_areas_from_csv(filename: &str, mode: LoadMode) -> Result<Loaded<MetroAreas>, LoadError> {
+    let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_path(filename).map_err(|error| LoadError::from_csv(filename, error))?;
+    let mut metro_areas: MetroAreas = HashMap::new();
+    let mut report = LoadReport::new(filename);
+
+    for result in csv_reader.records() {
+        let record = result.map_err(|error| LoadError::from_csv(filename, error))?;
+        let line = record.position().map(|position| position.line() as usize).unwrap_or(0);
+        let text = record.iter().collect::<Vec<_>>().join(",");
+        report.rows += 1;
+        if record.len() < 2 {
+            let reason = format!("expected 2 fields, found {}", record.len());
+            report.reject(mode, LoadError::MalformedRow { line, reason }, &text)?;
+            continue;
+        }
+        let airport = record[0].trim().to_string();
+        if metro_areas.contains_key(&airport) {
+            report.reject(mode, LoadError::DuplicateKey { line, key: airport }, &text)?;
+            continue;
+        }
+        metro_areas.insert(airport, record[1].trim().to_string());
+        report.accepted += 1;
+    }
+
+    Ok(Loaded { data: metro_areas, report })
+}
+
+// Members of every metro area that actually has routes, sorted by code
+fn members(adjacency_list: &AdjacencyList, metro_areas: &MetroAreas) -> BTreeMap<String, Vec<String>> {
+    let mut members: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (code, metro) in metro_areas {
+        if adjacency_list.contains_key(code) {
+            members.entry(metro.clone()).or_default().push(code.clone());
+        }
+    }
+    for codes in members.values_mut() {
+        codes.sort();
+    }
+    members
+}
+
+// Merge or link the airports of each metro area; collapsed metro areas are added to `airports` at their center
+pub fn apply_metro_areas(
+    adjacency_list: &AdjacencyList,
+    airports: &mut HashMap<String, Airport>,
+    metro_areas: &MetroAreas,
+    mode: MetroMode,
+) -> AdjacencyList {
+    let members = members(adjacency_list, metro_areas);
+
+    match mode {
+        MetroMode::Collapse => {
+            // Only metro areas with at least two served airports are worth merging
+            let node_of = |code: &String| -> String {
+                match metro_areas.get(code) {
+                    Some(metro) if members.get(metro).is_some_and(|codes| codes.len() > 1) => metro.clone(),
+                    _ => code.clone(),
+                }
+            };
+
+            let mut collapsed: AdjacencyList = HashMap::new();
+            for (airport, neighbors) in adjacency_list {
+                let node = node_of(airport);
+                let entry = collapsed.entry(node.clone()).or_default();
+                for (neighbor, distance) in neighbors {
+                    let other = node_of(neighbor);
+                    if other != node {
+                        entry.push((other, *distance));
+                    }
+                }
+            }
+
+            for (metro, codes) in &members {
+                if codes.len() > 1 && !airports.contains_key(metro) {
+                    let first = &airports[&codes[0]];
+                    let locations: Vec<&Location> = codes.iter().map(|code| &airports[code].location).collect();
+                    let center = Location::center(&locations);
+                    let metro_airport = Airport::new(metro, &first.city, &first.country, center.latitude(), center.longitude());
+                    airports.insert(metro.clone(), metro_airport);
+                }
+            }
+
+            collapsed
+        }
+        MetroMode::Link => {
+            let mut linked = adjacency_list.clone();
+            for codes in members.values() {
+                for a in codes {
+                    for b in codes {
+                        if a != b {
+                            linked.get_mut(a).unwrap().push((b.clone(), Distance::ZERO));
+                        }
+                    }
+                }
+            }
+            linked
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bfs;
+
+    #[test]
+    fn test_collapse_metro_areas() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("LGW".to_string(), Airport::new("London Gatwick Airport", "London", "United Kingdom", 51.148102, -0.190278));
+        airports.insert("JFK".to_string(), Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789));
+        airports.insert("EDI".to_string(), Airport::new("Edinburgh Airport", "Edinburgh", "United Kingdom", 55.950145, -3.372288));
+
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        let routes = [("LHR", "JFK"), ("LGW", "EDI"), ("LHR", "LGW")];
+        for (from, to) in routes {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(1.0)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(1.0)));
+        }
+
+        let metro_areas = metro_areas_from_cities(&airports, 100.0);
+        assert_eq!(metro_areas.len(), 2);
+
+        // JFK to EDI now takes two flights instead of three
+        let collapsed = apply_metro_areas(&adjacency_list, &mut airports, &metro_areas, MetroMode::Collapse);
+        assert_eq!(collapsed.len(), 3);
+        assert_eq!(bfs::hop_counts(&collapsed, "JFK")["EDI"], 2);
+        assert!(airports.contains_key("London, United Kingdom"));
+
+        let linked = apply_metro_areas(&adjacency_list, &mut airports, &metro_areas, MetroMode::Link);
+        assert!(linked["LGW"].contains(&("LHR".to_string(), Distance::from_km(0.0))));
+    }
+
+    #[test]
+    fn test_metro_areas_split_far_apart_cities() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("PDX".to_string(), Airport::new("Portland International Airport", "Portland", "United States", 45.58869934, -122.5979996));
+        airports.insert("HIO".to_string(), Airport::new("Portland Hillsboro Airport", "Portland", "United States", 45.540401, -122.949997));
+        airports.insert("PWM".to_string(), Airport::new("Portland International Jetport", "Portland", "United States", 43.64619827, -70.30930328));
+        airports.insert("1B1".to_string(), Airport::new("Portland Seaplane Base", "Portland", "United States", 43.656, -70.2459));
+        airports.insert("CMH".to_string(), Airport::new("Port Columbus International Airport", "Columbus", "United States", 39.998001, -82.891899));
+        airports.insert("CSG".to_string(), Airport::new("Columbus Metropolitan Airport", "Columbus", "United States", 32.516300, -84.938904));
+
+        let metro_areas = metro_areas_from_cities(&airports, 100.0);
+        assert_eq!(metro_areas["PDX"], "Portland, United States (HIO)");
+        assert_eq!(metro_areas["HIO"], metro_areas["PDX"]);
+        assert_eq!(metro_areas["PWM"], "Portland, United States (1B1)");
+        assert_eq!(metro_areas["1B1"], metro_areas["PWM"]);
+        // Columbus, Ohio and Columbus, Georgia have a single airport each
+        assert!(!metro_areas.contains_key("CMH") && !metro_areas.contains_key("CSG"));
+
+        // Within a large enough distance they form one area again
+        assert_eq!(metro_areas_from_cities(&airports, 5000.0)["PWM"], "Portland, United States");
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use crate::distance::Distance;
+use crate::graph::{simple_graph, AdjacencyList};
+
+// Minimum spanning tree of one connected component
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningTree {
+    pub airports: Vec<String>,
+    // (from, to, distance)
+    pub edges: Vec<(String, String, Distance)>,
+    pub length: Distance,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningForest {
+    pub trees: Vec<SpanningTree>,
+    pub total_length: Distance,
+}
+
+// Union-find over airport indices with path halving and union by size
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        DisjointSet { parent: (0..len).collect(), size: vec![1; len] }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+// Kruskal's algorithm over the kilometer-weighted routes, one tree per connected component
+pub fn minimum_spanning_forest(adjacency_list: &AdjacencyList) -> SpanningForest {
+    let simple = simple_graph(adjacency_list);
+    let mut airports: Vec<&String> = simple.keys().collect();
+    airports.sort();
+    let index: HashMap<&String, usize> = airports.iter().enumerate().map(|(i, &a)| (a, i)).collect();
+
+    let mut routes: Vec<(Distance, usize, usize)> = Vec::new();
+    for (airport, neighbors) in &simple {
+        for (neighbor, distance) in neighbors {
+            if airport < neighbor {
+                routes.push((*distance, index[airport], index[neighbor]));
+            }
+        }
+    }
+    routes.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| (a.1, a.2).cmp(&(b.1, b.2))));
+
+    let mut sets = DisjointSet::new(airports.len());
+    let mut chosen: Vec<(Distance, usize, usize)> = Vec::new();
+    for (distance, a, b) in routes {
+        if sets.union(a, b) {
+            chosen.push((distance, a, b));
+        }
+    }
+
+    // Group airports and chosen routes by the tree they ended up in
+    let mut trees: HashMap<usize, SpanningTree> = HashMap::new();
+    for (i, airport) in airports.iter().enumerate() {
+        let root = sets.find(i);
+        trees
+            .entry(root)
+            .or_insert_with(|| SpanningTree { airports: vec![], edges: vec![], length: Distance::ZERO })
+            .airports
+            .push((*airport).clone());
+    }
+    for (distance, a, b) in chosen {
+        let tree = trees.get_mut(&sets.find(a)).unwrap();
+        tree.edges.push((airports[a].clone(), airports[b].clone(), distance));
+        tree.length += distance;
+    }
+
+    let mut trees: Vec<SpanningTree> = trees.into_values().collect();
+    trees.sort_by(|a, b| b.airports.len().cmp(&a.airports.len()).then_with(|| a.airports.cmp(&b.airports)));
+    let total_length = trees.iter().map(|tree| tree.length).sum();
+
+    SpanningForest { trees, total_length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_minimum_spanning_forest() {
+        // Square A - B - C - D with a long diagonal, plus a separate pair X - Y
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        let routes = [("A", "B", 1.0), ("B", "C", 2.0), ("C", "D", 3.0), ("D", "A", 4.0), ("A", "C", 5.0), ("X", "Y", 7.0)];
+        for (from, to, distance) in routes {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(distance)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(distance)));
+        }
+
+        let forest = minimum_spanning_forest(&adjacency_list);
+
+        assert_eq!(forest.trees.len(), 2);
+        assert_eq!(forest.trees[0].airports, vec!["A", "B", "C", "D"]);
+        assert_eq!(forest.trees[0].length, Distance::from_km(6.0));
+        assert_eq!(forest.trees[0].edges.len(), 3);
+        assert_eq!(forest.trees[1].length, Distance::from_km(7.0));
+        assert_eq!(forest.total_length, Distance::from_km(13.0));
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use crate::graph::{connected_components, simple_graph, subgraph, AdjacencyList};
+use crate::metrics::average_path_length;
+
+// Order in which airports are closed during the experiment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemovalOrder {
+    // Random failures, shuffled with the given seed
+    Random(u64),
+    // Targeted attack on the best connected airports first
+    Degree,
+    // Targeted attack on the airports most shortest paths pass through first
+    Betweenness,
+}
+
+// State of the network after a number of airports have been removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobustnessStep {
+    pub removed: usize,
+    pub last_removed: Option<String>,
+    pub giant_component_size: usize,
+    pub average_path_length: f64,
+}
+
+// Betweenness centrality (Brandes) counting fewest-flight paths between every pair of airports
+pub fn betweenness_centrality(adjacency_list: &AdjacencyList) -> HashMap<String, f64> {
+    let mut centrality: HashMap<String, f64> = adjacency_list.keys().map(|airport| (airport.clone(), 0.0)).collect();
+
+    for source in adjacency_list.keys() {
+        let mut order: Vec<&String> = Vec::new();
+        let mut predecessors: HashMap<&String, Vec<&String>> = HashMap::new();
+        let mut path_counts: HashMap<&String, f64> = HashMap::new();
+        let mut hops: HashMap<&String, usize> = HashMap::new();
+        let mut queue: VecDeque<&String> = VecDeque::new();
+
+        path_counts.insert(source, 1.0);
+        hops.insert(source, 0);
+        queue.push_back(source);
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for (neighbor, _) in &adjacency_list[node] {
+                if !hops.contains_key(neighbor) {
+                    hops.insert(neighbor, hops[node] + 1);
+                    queue.push_back(neighbor);
+                }
+                if hops[neighbor] == hops[node] + 1 {
+                    *path_counts.entry(neighbor).or_default() += path_counts[node];
+                    predecessors.entry(neighbor).or_default().push(node);
+                }
+            }
+        }
+
+        // Accumulate dependencies from the farthest airports back towards the source
+        let mut dependency: HashMap<&String, f64> = HashMap::new();
+        for &node in order.iter().rev() {
+            let node_dependency = dependency.get(node).copied().unwrap_or(0.0);
+            if let Some(previous) = predecessors.get(node) {
+                for &prev in previous {
+                    let share = path_counts[prev] / path_counts[node] * (1.0 + node_dependency);
+                    *dependency.entry(prev).or_default() += share;
+                }
+            }
+            if node != source {
+                *centrality.get_mut(node).unwrap() += node_dependency;
+            }
+        }
+    }
+
+    // Every undirected path was counted once from each end
+    for value in centrality.values_mut() {
+        *value /= 2.0;
+    }
+
+    centrality
+}
+
+// The airports in the order they will be removed, ranked on the intact network
+pub fn removal_sequence(adjacency_list: &AdjacencyList, order: RemovalOrder) -> Vec<String> {
+    let simple = simple_graph(adjacency_list);
+    let mut airports: Vec<String> = simple.keys().cloned().collect();
+    airports.sort();
+
+    match order {
+        RemovalOrder::Random(seed) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            airports.shuffle(&mut rng);
+        }
+        RemovalOrder::Degree => {
+            airports.sort_by_key(|airport| std::cmp::Reverse(simple[airport].len()));
+        }
+        RemovalOrder::Betweenness => {
+            let centrality = betweenness_centrality(&simple);
+            airports.sort_by(|a, b| centrality[b].total_cmp(&centrality[a]));
+        }
+    }
+
+    airports
+}
+
+// Remove airports one by one and record the giant component and average path length every `step` removals
+pub fn simulate_removal(
+    adjacency_list: &AdjacencyList,
+    order: RemovalOrder,
+    step: usize,
+    path_samples: usize,
+) -> Vec<RobustnessStep> {
+    let sequence = removal_sequence(adjacency_list, order);
+    let mut remaining: HashSet<String> = sequence.iter().cloned().collect();
+    let simple = simple_graph(adjacency_list);
+    let seed = match order {
+        RemovalOrder::Random(seed) => seed,
+        _ => 0,
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let step = step.max(1);
+
+    let mut record = |removed: usize, last_removed: Option<String>, remaining: &HashSet<String>| {
+        let graph = subgraph(&simple, remaining);
+        let largest: HashSet<String> = connected_components(&graph).into_iter().next().unwrap_or_default().into_iter().collect();
+        let giant = subgraph(&graph, &largest);
+        RobustnessStep {
+            removed,
+            last_removed,
+            giant_component_size: giant.len(),
+            average_path_length: average_path_length(&giant, path_samples, &mut rng),
+        }
+    };
+
+    let mut steps = vec![record(0, None, &remaining)];
+    for (index, airport) in sequence.iter().enumerate() {
+        remaining.remove(airport);
+        let removed = index + 1;
+        if removed % step == 0 || removed == sequence.len() {
+            steps.push(record(removed, Some(airport.clone()), &remaining));
+        }
+    }
+
+    steps
+}
+
+// Write the degradation curve as CSV for plotting
+pub fn write_degradation_csv(filename: &str, steps: &[RobustnessStep]) -> Result<(), Box<dyn Error>> {
+    let mut csv_writer = csv::Writer::from_path(filename)?;
+    csv_writer.write_record(["removed", "last_removed", "giant_component_size", "average_path_length"])?;
+
+    for step in steps {
+        csv_writer.write_record(&[
+            step.removed.to_string(),
+            step.last_removed.clone().unwrap_or_default(),
+            step.giant_component_size.to_string(),
+            format!("{:.4}", step.average_path_length),
+        ])?;
+    }
+
+    csv_writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_targeted_removal() {
+        // Star around hub H plus an isolated pair X - Y
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        adjacency_list.insert("H".to_string(), vec![("A".to_string(), Distance::from_km(1.0)), ("B".to_string(), Distance::from_km(1.0)), ("C".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("A".to_string(), vec![("H".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("B".to_string(), vec![("H".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("C".to_string(), vec![("H".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("X".to_string(), vec![("Y".to_string(), Distance::from_km(1.0))]);
+        adjacency_list.insert("Y".to_string(), vec![("X".to_string(), Distance::from_km(1.0))]);
+
+        let steps = simulate_removal(&adjacency_list, RemovalOrder::Degree, 1, 10);
+
+        assert_eq!(steps[0].giant_component_size, 4);
+        assert_eq!(steps[0].average_path_length, 1.5);
+        assert_eq!(steps[1].last_removed, Some("H".to_string()));
+        assert_eq!(steps[1].giant_component_size, 2);
+        assert_eq!(steps.last().unwrap().giant_component_size, 0);
+
+        let centrality = betweenness_centrality(&adjacency_list);
+        assert_eq!(centrality["H"], 3.0);
+        assert_eq!(centrality["A"], 0.0);
+    }
+}
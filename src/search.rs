@@ -0,0 +1,188 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::error::Error;
+use crate::airports::Airport;
+
+// Which field of the airport the query matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Iata,
+    Icao,
+    Name,
+    City,
+    Country,
+}
+
+// One ranked search result; lower scores are better
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub code: String,
+    pub kind: MatchKind,
+    pub score: usize,
+}
+
+// Edit distance between two strings where an insertion, deletion, substitution or swap of
+// two neighbouring characters each count as one typo (optimal string alignment)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    table[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = table[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            table[i][j] = substitution.min(table[i - 1][j] + 1).min(table[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+// How well the query matches one text field: exact, then prefix, then substring, then a close typo
+// of the whole field or of a run of as many consecutive words as the query has; None when it is too far off
+fn field_score(query: &str, field: &str) -> Option<usize> {
+    let field = field.to_lowercase();
+    if field.is_empty() {
+        return None;
+    }
+    if field == query {
+        return Some(0);
+    }
+    if field.starts_with(query) {
+        return Some(1);
+    }
+    if field.contains(query) {
+        return Some(2);
+    }
+
+    // Allow roughly one typo per four characters
+    let tolerance = (query.chars().count() / 4).max(1);
+    let words: Vec<&str> = field.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    let query_words = query.split_whitespace().count().clamp(1, words.len().max(1));
+    let closest = std::iter::once(field.clone())
+        .chain(words.windows(query_words).map(|run| run.join(" ")))
+        .map(|run| edit_distance(query, &run))
+        .min()?;
+    (closest <= tolerance).then_some(3 + closest)
+}
+
+// Airports matching the query by IATA or ICAO code, name, city or country, best first
+pub fn search_airports(airports: &HashMap<String, Airport>, query: &str, limit: usize) -> Vec<Candidate> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let mut candidates: Vec<Candidate> = airports
+        .iter()
+        .filter_map(|(code, airport)| {
+            // An exact code beats any text match; a code one typo away ranks just ahead of misspelled names
+            let codes = [(MatchKind::Iata, code.as_str()), (MatchKind::Icao, airport.icao.as_str())];
+            let code_match = codes
+                .iter()
+                .filter(|(_, value)| !value.is_empty() && *value != "\\N")
+                .filter_map(|(kind, value)| match edit_distance(&query, &value.to_lowercase()) {
+                    0 => Some((*kind, 0)),
+                    1 => Some((*kind, 4)),
+                    _ => None,
+                });
+            let fields = [(MatchKind::Name, &airport.name), (MatchKind::City, &airport.city), (MatchKind::Country, &airport.country)];
+            let text_match = fields
+                .iter()
+                .filter_map(|(kind, value)| field_score(&query, value).map(|score| (*kind, score + 1)));
+
+            code_match
+                .chain(text_match)
+                .min_by_key(|(kind, score)| (*score, *kind))
+                .map(|(kind, score)| Candidate { code: code.clone(), kind, score })
+        })
+        .collect();
+
+    // Among equally close matches, codes sharing more leading letters with the query come first, then busier
+    // airports, so that LHX suggests LHR before LAX or Lahr
+    let rank = |candidate: &Candidate| {
+        let shared_prefix = candidate.code.to_lowercase().chars().zip(query.chars()).take_while(|(a, b)| a == b).count();
+        (candidate.score, candidate.kind, Reverse(shared_prefix), Reverse(airports[&candidate.code].routes))
+    };
+    candidates.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.code.cmp(&b.code)));
+    candidates.truncate(limit);
+    candidates
+}
+
+// Airport code for an exact IATA or ICAO code, or an error suggesting the closest airports
+pub fn resolve_airport(airports: &HashMap<String, Airport>, text: &str) -> Result<String, Box<dyn Error>> {
+    let text = text.trim();
+    if airports.contains_key(text) {
+        return Ok(text.to_string());
+    }
+
+    let candidates = search_airports(airports, text, 5);
+    match candidates.first() {
+        Some(best) if best.score == 0 && matches!(best.kind, MatchKind::Iata | MatchKind::Icao) => Ok(best.code.clone()),
+        _ => Err(format!("Unknown airport: {}{}", text, did_you_mean(airports, &candidates)).into()),
+    }
+}
+
+// ". Did you mean LHR (London Heathrow Airport, London), ...?", or nothing without candidates
+pub fn did_you_mean(airports: &HashMap<String, Airport>, candidates: &[Candidate]) -> String {
+    if candidates.is_empty() {
+        return String::new();
+    }
+    let suggestions: Vec<String> = candidates
+        .iter()
+        .map(|candidate| {
+            let airport = &airports[&candidate.code];
+            format!("{} ({}, {})", candidate.code, airport.name, airport.city)
+        })
+        .collect();
+    format!(". Did you mean {}?", suggestions.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::itinerary::Place;
+
+    #[test]
+    fn test_airport_search() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("LHR".to_string(), Airport { icao: "EGLL".to_string(), ..Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941) });
+        airports.insert("LGW".to_string(), Airport { icao: "EGKK".to_string(), ..Airport::new("London Gatwick Airport", "London", "United Kingdom", 51.148102, -0.190278) });
+        airports.insert("CDG".to_string(), Airport { icao: "LFPG".to_string(), ..Airport::new("Charles de Gaulle International Airport", "Paris", "France", 49.012798, 2.55) });
+
+        assert_eq!(edit_distance("heathrw", "heathrow"), 1);
+        assert_eq!(edit_distance("lodnon", "london"), 1);
+        assert_eq!(resolve_airport(&airports, "egll").unwrap(), "LHR");
+        assert_eq!(resolve_airport(&airports, "cdg").unwrap(), "CDG");
+
+        // A typo in the name still finds the airport, ahead of weaker matches
+        let found = search_airports(&airports, "Heathorw", 5);
+        assert_eq!(found[0].code, "LHR");
+        assert_eq!(found[0].kind, MatchKind::Name);
+        let london: Vec<String> = search_airports(&airports, "london", 5).into_iter().map(|candidate| candidate.code).collect();
+        assert_eq!(london, vec!["LGW", "LHR"]);
+
+        let error = resolve_airport(&airports, "LHX").unwrap_err().to_string();
+        assert!(error.starts_with("Unknown airport: LHX. Did you mean LHR (London Heathrow Airport, London)"), "{}", error);
+        assert_eq!(Place::parse("EGKK", &airports).unwrap(), Place::Airport("LGW".to_string()));
+
+        // A typo in one word of a multi-word name
+        assert_eq!(search_airports(&airports, "charles de gaule", 5)[0].code, "CDG");
+
+        // Equally close codes rank by shared leading letters, then by routes
+        airports.insert("LAX".to_string(), Airport { routes: 900, ..Airport::new("Los Angeles International Airport", "Los Angeles", "United States", 33.942501, -118.407997) });
+        airports.insert("LHA".to_string(), Airport { routes: 2, ..Airport::new("Lahr Airport", "Lahr", "Germany", 48.3693008423, 7.82772016525) });
+        airports.get_mut("LHR").unwrap().routes = 500;
+        let suggested: Vec<String> = search_airports(&airports, "LHX", 5).into_iter().map(|candidate| candidate.code).collect();
+        assert_eq!(suggested, vec!["LHR", "LHA", "LAX"]);
+
+        // Code-looking text that is no airport or city is reported as an unknown airport, not as a city
+        let error = Place::parse("LHX", &airports).unwrap_err().to_string();
+        assert!(error.starts_with("Unknown airport: LHX. Did you mean LHR"), "{}", error);
+        assert_eq!(Place::parse("Lahr", &airports).unwrap(), Place::City("Lahr".to_string()));
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{HashMap, HashSet};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::distance::Distance;
+use crate::graph::{giant_component, simple_graph, AdjacencyList};
+use crate::metrics::average_path_length;
+
+// Observed network compared against random graphs with the same degree sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmallWorldComparison {
+    pub observed_clustering: f64,
+    pub observed_transitivity: f64,
+    pub observed_path_length: f64,
+    pub random_clustering: f64,
+    pub random_transitivity: f64,
+    pub random_path_length: f64,
+    // (C / C_random) / (L / L_random); well above 1 for a small-world network, None when a ratio is undefined
+    pub sigma: Option<f64>,
+}
+
+// Neighbor sets of the deduplicated graph, for fast triangle checks
+fn neighbor_sets(adjacency_list: &AdjacencyList) -> HashMap<&String, HashSet<&String>> {
+    adjacency_list
+        .iter()
+        .map(|(airport, neighbors)| (airport, neighbors.iter().map(|(neighbor, _)| neighbor).filter(|n| *n != airport).collect()))
+        .collect()
+}
+
+// Number of routes between the neighbors of every airport
+fn neighbor_links(adjacency_list: &AdjacencyList) -> HashMap<String, (usize, usize)> {
+    let sets = neighbor_sets(adjacency_list);
+    sets.iter()
+        .map(|(&airport, neighbors)| {
+            let list: Vec<&&String> = neighbors.iter().collect();
+            let mut links = 0;
+            for (i, a) in list.iter().enumerate() {
+                for b in &list[i + 1..] {
+                    if sets.get(**a).is_some_and(|set| set.contains(**b)) {
+                        links += 1;
+                    }
+                }
+            }
+            (airport.clone(), (links, neighbors.len()))
+        })
+        .collect()
+}
+
+// Fraction of each airport's neighbor pairs that are themselves connected (0 for fewer than two neighbors)
+pub fn local_clustering(adjacency_list: &AdjacencyList) -> HashMap<String, f64> {
+    neighbor_links(adjacency_list)
+        .into_iter()
+        .map(|(airport, (links, degree))| {
+            let pairs = degree * degree.saturating_sub(1) / 2;
+            let coefficient = if pairs > 0 { links as f64 / pairs as f64 } else { 0.0 };
+            (airport, coefficient)
+        })
+        .collect()
+}
+
+// Mean of the local clustering coefficients
+pub fn average_clustering(adjacency_list: &AdjacencyList) -> f64 {
+    let local = local_clustering(adjacency_list);
+    if local.is_empty() {
+        return 0.0;
+    }
+    local.values().sum::<f64>() / local.len() as f64
+}
+
+// Global clustering (transitivity): closed triplets over all connected triplets
+pub fn global_clustering(adjacency_list: &AdjacencyList) -> f64 {
+    let (closed, triplets) = neighbor_links(adjacency_list)
+        .values()
+        .fold((0, 0), |(closed, triplets), &(links, degree)| (closed + links, triplets + degree * degree.saturating_sub(1) / 2));
+    if triplets > 0 {
+        closed as f64 / triplets as f64
+    } else {
+        0.0
+    }
+}
+
+// Shuffle the routes with degree-preserving double edge swaps, keeping the graph free of loops and repeats
+pub fn degree_preserving_random_graph(adjacency_list: &AdjacencyList, swaps_per_edge: usize, rng: &mut StdRng) -> AdjacencyList {
+    let simple = simple_graph(adjacency_list);
+    let mut airports: Vec<&String> = simple.keys().collect();
+    airports.sort();
+    let index: HashMap<&String, usize> = airports.iter().enumerate().map(|(i, &a)| (a, i)).collect();
+
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (airport, neighbors) in &simple {
+        for (neighbor, _) in neighbors {
+            if airport < neighbor {
+                edges.push((index[airport], index[neighbor]));
+            }
+        }
+    }
+    edges.sort();
+    let mut present: HashSet<(usize, usize)> = edges.iter().copied().collect();
+    let key = |a: usize, b: usize| (a.min(b), a.max(b));
+
+    if edges.len() >= 2 {
+        for _ in 0..swaps_per_edge * edges.len() {
+            let i = rng.gen_range(0..edges.len());
+            let j = rng.gen_range(0..edges.len());
+            let (a, b) = edges[i];
+            let (c, d) = edges[j];
+            // Rewire a-b, c-d into a-d, c-b
+            if a == d || c == b || a == c || b == d || present.contains(&key(a, d)) || present.contains(&key(c, b)) {
+                continue;
+            }
+            present.remove(&key(a, b));
+            present.remove(&key(c, d));
+            present.insert(key(a, d));
+            present.insert(key(c, b));
+            edges[i] = key(a, d);
+            edges[j] = key(c, b);
+        }
+    }
+
+    let mut random: AdjacencyList = airports.iter().map(|&a| (a.clone(), Vec::new())).collect();
+    for (a, b) in edges {
+        random.get_mut(airports[a]).unwrap().push((airports[b].clone(), Distance::from_km(1.0)));
+        random.get_mut(airports[b]).unwrap().push((airports[a].clone(), Distance::from_km(1.0)));
+    }
+    random
+}
+
+// (C / C_random) / (L / L_random), or None when sparse graphs leave no triangles or paths to compare
+pub fn small_world_sigma(clustering: f64, random_clustering: f64, path_length: f64, random_path_length: f64) -> Option<f64> {
+    if random_clustering == 0.0 || path_length == 0.0 || random_path_length == 0.0 {
+        return None;
+    }
+    let sigma = (clustering / random_clustering) / (path_length / random_path_length);
+    sigma.is_finite().then_some(sigma)
+}
+
+// Compare clustering and average flights per trip against `random_graphs` seeded null models
+pub fn small_world_comparison(adjacency_list: &AdjacencyList, random_graphs: usize, path_samples: usize, seed: u64) -> SmallWorldComparison {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let simple = simple_graph(adjacency_list);
+
+    let observed_clustering = average_clustering(&simple);
+    let observed_transitivity = global_clustering(&simple);
+    let observed_path_length = average_path_length(&giant_component(&simple), path_samples, &mut rng);
+
+    let mut random_clustering = 0.0;
+    let mut random_transitivity = 0.0;
+    let mut random_path_length = 0.0;
+    let runs = random_graphs.max(1);
+    for _ in 0..runs {
+        let random = degree_preserving_random_graph(&simple, 10, &mut rng);
+        random_clustering += average_clustering(&random);
+        random_transitivity += global_clustering(&random);
+        random_path_length += average_path_length(&giant_component(&random), path_samples, &mut rng);
+    }
+    random_clustering /= runs as f64;
+    random_transitivity /= runs as f64;
+    random_path_length /= runs as f64;
+
+    let sigma = small_world_sigma(observed_clustering, random_clustering, observed_path_length, random_path_length);
+
+    SmallWorldComparison {
+        observed_clustering,
+        observed_transitivity,
+        observed_path_length,
+        random_clustering,
+        random_transitivity,
+        random_path_length,
+        sigma,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::Distance;
+
+    #[test]
+    fn test_clustering_and_null_model() {
+        // Triangle A - B - C with a pendant D on C
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        let routes = [("A", "B"), ("B", "C"), ("A", "C"), ("C", "D")];
+        for (from, to) in routes {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(1.0)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(1.0)));
+        }
+
+        let local = local_clustering(&adjacency_list);
+        assert_eq!(local["A"], 1.0);
+        assert!((local["C"] - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(local["D"], 0.0);
+        assert!((global_clustering(&adjacency_list) - 0.6).abs() < 1e-9);
+
+        // The null model keeps every airport's number of routes
+        let mut rng = rand::SeedableRng::seed_from_u64(7);
+        let random = degree_preserving_random_graph(&adjacency_list, 10, &mut rng);
+        for (airport, neighbors) in &adjacency_list {
+            assert_eq!(random[airport].len(), neighbors.len());
+        }
+    }
+
+    #[test]
+    fn test_small_world_sigma_undefined() {
+        assert_eq!(small_world_sigma(0.6, 0.3, 2.0, 2.0), Some(2.0));
+        // No triangles in the null model, or no paths at all
+        assert_eq!(small_world_sigma(0.6, 0.0, 2.0, 2.0), None);
+        assert_eq!(small_world_sigma(0.0, 0.0, 2.0, 2.0), None);
+        assert_eq!(small_world_sigma(0.6, 0.3, 0.0, 2.0), None);
+        assert_eq!(small_world_sigma(0.6, 0.3, 2.0, 0.0), None);
+
+        // A tree has no triangles, so its sigma is undefined rather than NaN
+        let mut adjacency_list: AdjacencyList = HashMap::new();
+        for (from, to) in [("A", "B"), ("B", "C"), ("C", "D")] {
+            adjacency_list.entry(from.to_string()).or_default().push((to.to_string(), Distance::from_km(1.0)));
+            adjacency_list.entry(to.to_string()).or_default().push((from.to_string(), Distance::from_km(1.0)));
+        }
+        assert_eq!(small_world_comparison(&adjacency_list, 2, 10, 7).sigma, None);
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use crate::airports::Airport;
+use crate::distance::EARTH_RADIUS_KM;
+
+// Airport position as a point on the unit sphere, so nearby airports across the antimeridian stay close
+fn unit_vector(latitude: f64, longitude: f64) -> [f64; 3] {
+    let (lat, lon) = (latitude.to_radians(), longitude.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+fn chord_squared(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (0..3).map(|axis| (a[axis] - b[axis]).powi(2)).sum()
+}
+
+// Great-circle distance in kilometers for a straight-line chord through the unit sphere
+fn chord_to_km(chord: f64) -> f64 {
+    2.0 * EARTH_RADIUS_KM * (chord / 2.0).min(1.0).asin()
+}
+
+fn km_to_chord(km: f64) -> f64 {
+    2.0 * (km / (2.0 * EARTH_RADIUS_KM)).min(std::f64::consts::FRAC_PI_2).sin()
+}
+
+#[derive(Debug, Clone)]
+struct KdNode {
+    code: String,
+    point: [f64; 3],
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+// k-d tree over the loaded airports for nearest-airport and radius queries
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    nodes: Vec<KdNode>,
+    root: Option<usize>,
+}
+
+impl SpatialIndex {
+    pub fn new(airports: &HashMap<String, Airport>) -> Self {
+        let mut points: Vec<(String, [f64; 3])> = airports
+            .iter()
+            .map(|(code, airport)| (code.clone(), unit_vector(airport.location.latitude(), airport.location.longitude())))
+            .collect();
+        points.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut index = SpatialIndex { nodes: Vec::with_capacity(points.len()), root: None };
+        index.root = index.build(&mut points, 0);
+        index
+    }
+
+    // Split on the median along the current axis and build both halves recursively
+    fn build(&mut self, points: &mut [(String, [f64; 3])], depth: usize) -> Option<usize> {
+        if points.is_empty() {
+            return None;
+        }
+        let axis = depth % 3;
+        let median = points.len() / 2;
+        points.select_nth_unstable_by(median, |a, b| a.1[axis].total_cmp(&b.1[axis]).then_with(|| a.0.cmp(&b.0)));
+
+        let (code, point) = points[median].clone();
+        let node = self.nodes.len();
+        self.nodes.push(KdNode { code, point, axis, left: None, right: None });
+
+        let (lower, upper) = points.split_at_mut(median);
+        let left = self.build(lower, depth + 1);
+        let right = self.build(&mut upper[1..], depth + 1);
+        self.nodes[node].left = left;
+        self.nodes[node].right = right;
+        Some(node)
+    }
+
+    // Visit every subtree that could hold a point closer than the current bound
+    fn search(&self, node: Option<usize>, target: &[f64; 3], visit: &mut dyn FnMut(&KdNode, f64) -> f64, bound: &mut f64) {
+        let Some(index) = node else {
+            return;
+        };
+        let current = &self.nodes[index];
+        *bound = visit(current, chord_squared(&current.point, target));
+
+        let offset = target[current.axis] - current.point[current.axis];
+        let (near, far) = if offset < 0.0 { (current.left, current.right) } else { (current.right, current.left) };
+        self.search(near, target, visit, bound);
+        if offset * offset <= *bound {
+            self.search(far, target, visit, bound);
+        }
+    }
+
+    // The `count` airports closest to a coordinate, nearest first, with great-circle distances in kilometers
+    pub fn nearest(&self, latitude: f64, longitude: f64, count: usize) -> Vec<(String, f64)> {
+        if count == 0 {
+            return vec![];
+        }
+        let target = unit_vector(latitude, longitude);
+        let mut best: Vec<(f64, String)> = Vec::with_capacity(count + 1);
+
+        let mut visit = |node: &KdNode, distance: f64| {
+            if best.len() < count || distance < best[best.len() - 1].0 {
+                let position = best.partition_point(|(d, code)| (*d, code) < (distance, &node.code));
+                best.insert(position, (distance, node.code.clone()));
+                best.truncate(count);
+            }
+            if best.len() < count {
+                f64::INFINITY
+            } else {
+                best[best.len() - 1].0
+            }
+        };
+        let mut bound = f64::INFINITY;
+        self.search(self.root, &target, &mut visit, &mut bound);
+
+        best.into_iter().map(|(distance, code)| (code, chord_to_km(distance.sqrt()))).collect()
+    }
+
+    // Every airport within `radius_km` of a coordinate, nearest first
+    pub fn within(&self, latitude: f64, longitude: f64, radius_km: f64) -> Vec<(String, f64)> {
+        let target = unit_vector(latitude, longitude);
+        let limit = km_to_chord(radius_km).powi(2);
+        let mut found: Vec<(f64, String)> = Vec::new();
+
+        let mut visit = |node: &KdNode, distance: f64| {
+            if distance <= limit {
+                found.push((distance, node.code.clone()));
+            }
+            limit
+        };
+        let mut bound = limit;
+        self.search(self.root, &target, &mut visit, &mut bound);
+
+        found.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        found.into_iter().map(|(distance, code)| (code, chord_to_km(distance.sqrt()))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spatial_index() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("LGW".to_string(), Airport::new("London Gatwick Airport", "London", "United Kingdom", 51.148102, -0.190278));
+        airports.insert("CDG".to_string(), Airport::new("Charles de Gaulle International Airport", "Paris", "France", 49.012798, 2.55));
+        airports.insert("JFK".to_string(), Airport::new("John F Kennedy International Airport", "New York", "United States", 40.639801, -73.7789));
+        // Either side of the antimeridian
+        airports.insert("SUV".to_string(), Airport::new("Nausori International Airport", "Nausori", "Fiji", -18.043301, 178.559006));
+        airports.insert("TVU".to_string(), Airport::new("Matei Airport", "Matei", "Fiji", -16.6906, -179.876999));
+
+        let index = SpatialIndex::new(&airports);
+
+        let nearest = index.nearest(51.5074, -0.1278, 2);
+        assert_eq!(nearest.iter().map(|(code, _)| code.as_str()).collect::<Vec<_>>(), vec!["LHR", "LGW"]);
+        assert!((nearest[0].1 - 23.5).abs() < 1.0);
+
+        let within = index.within(51.5074, -0.1278, 400.0);
+        assert_eq!(within.len(), 3);
+        assert_eq!(within[2].0, "CDG");
+
+        let across = index.nearest(-17.0, 179.9, 2);
+        assert_eq!(across[0].0, "TVU");
+        assert_eq!(across[1].0, "SUV");
+    }
+}
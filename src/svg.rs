@@ -0,0 +1,133 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::airports::Airport;
+use crate::distance::haversine_km;
+use crate::great_circle::{intermediate_points, split_at_antimeridian};
+
+// Robinson projection table at every 5° of latitude: (parallel length, distance from the equator)
+const ROBINSON: [(f64, f64); 19] = [
+    (1.0000, 0.0000), (0.9986, 0.0620), (0.9954, 0.1240), (0.9900, 0.1860), (0.9822, 0.2480),
+    (0.9730, 0.3100), (0.9600, 0.3720), (0.9427, 0.4340), (0.9216, 0.4958), (0.8962, 0.5571),
+    (0.8679, 0.6176), (0.8350, 0.6769), (0.7986, 0.7346), (0.7597, 0.7903), (0.7186, 0.8435),
+    (0.6732, 0.8936), (0.6213, 0.9394), (0.5722, 0.9761), (0.5322, 1.0000),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Equirectangular,
+    Robinson,
+}
+
+impl Projection {
+    // Projected (x, y) with x in [-1, 1] and y growing northwards
+    fn project(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        match self {
+            Projection::Equirectangular => (longitude / 180.0, latitude / 180.0),
+            Projection::Robinson => {
+                let position = (latitude.abs() / 5.0).min(18.0);
+                let i = (position.floor() as usize).min(17);
+                let fraction = position - i as f64;
+                let length = ROBINSON[i].0 + fraction * (ROBINSON[i + 1].0 - ROBINSON[i].0);
+                let height = ROBINSON[i].1 + fraction * (ROBINSON[i + 1].1 - ROBINSON[i].1);
+                (length * longitude / 180.0, latitude.signum() * height * 1.3523 / (0.8487 * std::f64::consts::PI))
+            }
+        }
+    }
+
+    // Height of the map relative to its width
+    fn aspect(&self) -> f64 {
+        match self {
+            Projection::Equirectangular => 0.5,
+            Projection::Robinson => 1.3523 / (0.8487 * std::f64::consts::PI),
+        }
+    }
+}
+
+// What to draw: the routes as airport pairs and the airports sized by their number of destinations
+pub struct MapLayer<'a> {
+    pub routes: Vec<(String, String)>,
+    pub degrees: &'a HashMap<String, usize>,
+}
+
+// Render the routes as great circles and their airports as circles on a world map
+pub fn render_svg(airports: &HashMap<String, Airport>, layer: &MapLayer, projection: Projection, width: f64) -> String {
+    let height = width * projection.aspect();
+    let to_pixels = |latitude: f64, longitude: f64| {
+        let (x, y) = projection.project(latitude, longitude);
+        ((x + 1.0) / 2.0 * width, height / 2.0 - y * width / 2.0)
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\">\n",
+        w = width,
+        h = height
+    );
+
+    // Outline of the globe, traced along the map edges at ±180°
+    let mut outline: Vec<String> = Vec::new();
+    for latitude in (-90..=90).step_by(5) {
+        let (x, y) = to_pixels(latitude as f64, -180.0);
+        outline.push(format!("{:.1},{:.1}", x, y));
+    }
+    for latitude in (-90..=90).rev().step_by(5) {
+        let (x, y) = to_pixels(latitude as f64, 180.0);
+        outline.push(format!("{:.1},{:.1}", x, y));
+    }
+    svg.push_str(&format!("<polygon points=\"{}\" fill=\"#dbe9f4\" stroke=\"#7a9cb8\"/>\n", outline.join(" ")));
+
+    svg.push_str("<g fill=\"none\" stroke=\"#c0392b\" stroke-opacity=\"0.35\" stroke-width=\"0.6\">\n");
+    let mut drawn: BTreeSet<&String> = BTreeSet::new();
+    for (from, to) in &layer.routes {
+        let (Some(start), Some(end)) = (airports.get(from), airports.get(to)) else {
+            continue;
+        };
+        drawn.insert(from);
+        drawn.insert(to);
+        let distance_km = haversine_km(&start.location, &end.location);
+        let points = intermediate_points(&start.location, &end.location, (distance_km / 200.0).ceil() as usize);
+        for part in split_at_antimeridian(&points) {
+            let coordinates: Vec<String> = part
+                .iter()
+                .map(|(lat, lon)| {
+                    let (x, y) = to_pixels(*lat, *lon);
+                    format!("{:.1},{:.1}", x, y)
+                })
+                .collect();
+            svg.push_str(&format!("<polyline points=\"{}\"/>\n", coordinates.join(" ")));
+        }
+    }
+    svg.push_str("</g>\n");
+
+    svg.push_str("<g fill=\"#1f3a5f\" fill-opacity=\"0.8\">\n");
+    for code in drawn {
+        let airport = &airports[code];
+        let (x, y) = to_pixels(airport.location.latitude(), airport.location.longitude());
+        let degree = layer.degrees.get(code).copied().unwrap_or(0);
+        let radius = 0.8 + (degree as f64).sqrt() * 0.35;
+        svg.push_str(&format!("<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.2}\"><title>{}</title></circle>\n", x, y, radius, code));
+    }
+    svg.push_str("</g>\n</svg>\n");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_svg() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("NAN".to_string(), Airport::new("Nadi International Airport", "Nandi", "Fiji", -17.755399703979492, 177.4429931640625));
+        airports.insert("HNL".to_string(), Airport::new("Daniel K Inouye International Airport", "Honolulu", "United States", 21.32062, -157.924228));
+        let degrees: HashMap<String, usize> = [("NAN".to_string(), 4), ("HNL".to_string(), 16)].into_iter().collect();
+        let layer = MapLayer { routes: vec![("NAN".to_string(), "HNL".to_string())], degrees: &degrees };
+
+        let map = render_svg(&airports, &layer, Projection::Equirectangular, 360.0);
+
+        assert!(map.starts_with("<svg") && map.trim_end().ends_with("</svg>"));
+        // The route crosses the antimeridian, so it is drawn in two pieces
+        assert_eq!(map.matches("<polyline").count(), 2);
+        assert_eq!(map.matches("<circle").count(), 2);
+        assert!(map.contains("r=\"2.20\"><title>HNL</title>"));
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use csv::{ReaderBuilder, WriterBuilder};
+use crate::aircraft::{is_known_equipment, typical_range_km, Aircraft};
+use crate::airlines::Airline;
+use crate::airports::Airport;
+use crate::distance::haversine_km;
+use crate::format::InputFormat;
+use crate::graph::RouteColumns;
+use crate::load::LoadError;
+
+// Legs may exceed the typical range of their aircraft by this factor before they count as implausible
+const RANGE_TOLERANCE: f64 = 1.15;
+
+// One row of the routes file with every column kept, so clean rows can be written back unchanged
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteRecord {
+    pub line: usize,
+    pub fields: Vec<String>,
+    // Where the fields are in the file's format
+    pub columns: RouteColumns,
+}
+
+impl RouteRecord {
+    fn field(&self, index: usize) -> &str {
+        self.fields.get(index).map(String::as_str).unwrap_or("")
+    }
+
+    pub fn airline(&self) -> &str {
+        self.field(self.columns.airline)
+    }
+
+    pub fn airline_id(&self) -> &str {
+        self.field(self.columns.airline_id)
+    }
+
+    pub fn from(&self) -> &str {
+        self.field(self.columns.from)
+    }
+
+    pub fn to(&self) -> &str {
+        self.field(self.columns.to)
+    }
+
+    // Aircraft codes of the Equipment column, which lists several types separated by spaces
+    pub fn equipment(&self) -> Vec<&str> {
+        self.field(self.columns.equipment).split_whitespace().collect()
+    }
+}
+
+// A check applied to every route; rules may keep state across rows, e.g. to spot duplicates
+pub trait RouteRule {
+    // Short name written to the rejects file, e.g. "self-loop"
+    fn name(&self) -> &'static str;
+    // Why the route fails the rule, or None when it passes
+    fn check(&mut self, route: &RouteRecord) -> Option<String>;
+}
+
+// Both airports must be in airports.csv
+pub struct UnknownAirports<'a> {
+    pub airports: &'a HashMap<String, Airport>,
+}
+
+impl RouteRule for UnknownAirports<'_> {
+    fn name(&self) -> &'static str {
+        "unknown-airport"
+    }
+
+    fn check(&mut self, route: &RouteRecord) -> Option<String> {
+        let unknown: Vec<&str> = [route.from(), route.to()].into_iter().filter(|code| !self.airports.contains_key(*code)).collect();
+        (!unknown.is_empty()).then(|| format!("unknown airport {}", unknown.join(" and ")))
+    }
+}
+
+// A route must leave its airport
+pub struct SelfLoops;
+
+impl RouteRule for SelfLoops {
+    fn name(&self) -> &'static str {
+        "self-loop"
+    }
+
+    fn check(&mut self, route: &RouteRecord) -> Option<String> {
+        (route.from() == route.to()).then(|| format!("route from {} to itself", route.from()))
+    }
+}
+
+// Every column but the index, if the format has one, must differ from the earlier rows
+#[derive(Default)]
+pub struct DuplicateRows {
+    seen: HashMap<Vec<String>, usize>,
+}
+
+impl RouteRule for DuplicateRows {
+    fn name(&self) -> &'static str {
+        "duplicate"
+    }
+
+    fn check(&mut self, route: &RouteRecord) -> Option<String> {
+        let key = route.fields.iter().skip(route.columns.airline).cloned().collect();
+        match self.seen.get(&key) {
+            Some(first) => Some(format!("same as line {}", first)),
+            None => {
+                self.seen.insert(key, route.line);
+                None
+            }
+        }
+    }
+}
+
+// The airline must still be flying according to airlines.csv
+pub struct InactiveAirlines<'a> {
+    pub airlines: &'a HashMap<String, Airline>,
+}
+
+impl RouteRule for InactiveAirlines<'_> {
+    fn name(&self) -> &'static str {
+        "inactive-airline"
+    }
+
+    fn check(&mut self, route: &RouteRecord) -> Option<String> {
+        let airline = self.airlines.get(route.airline_id())?;
+        (!airline.active).then(|| format!("{} ({}) is not active", airline.name, route.airline()))
+    }
+}
+
+// Every listed aircraft type must be in airplanes.csv or be one of its known variant codes
+pub struct UnknownEquipment<'a> {
+    pub aircraft: &'a HashMap<String, Aircraft>,
+}
+
+impl RouteRule for UnknownEquipment<'_> {
+    fn name(&self) -> &'static str {
+        "unknown-equipment"
+    }
+
+    fn check(&mut self, route: &RouteRecord) -> Option<String> {
+        let unknown: Vec<&str> = route.equipment().into_iter().filter(|code| !is_known_equipment(self.aircraft, code)).collect();
+        (!unknown.is_empty()).then(|| format!("unknown equipment {}", unknown.join(" ")))
+    }
+}
+
+// The leg must be within reach of at least one listed aircraft; types without a known range are given the benefit of the doubt
+pub struct ImplausibleLegs<'a> {
+    pub airports: &'a HashMap<String, Airport>,
+}
+
+impl RouteRule for ImplausibleLegs<'_> {
+    fn name(&self) -> &'static str {
+        "implausible-leg"
+    }
+
+    fn check(&mut self, route: &RouteRecord) -> Option<String> {
+        let (from, to) = (self.airports.get(route.from())?, self.airports.get(route.to())?);
+        let ranges: Vec<Option<f64>> = route.equipment().into_iter().map(typical_range_km).collect();
+        if ranges.is_empty() || ranges.contains(&None) {
+            return None;
+        }
+        let longest_range = ranges.into_iter().flatten().fold(0.0, f64::max);
+        let distance_km = haversine_km(&from.location, &to.location);
+        (distance_km > longest_range * RANGE_TOLERANCE)
+            .then(|| format!("{:.0} km leg beyond the {:.0} km range of {}", distance_km, longest_range, route.equipment().join(" ")))
+    }
+}
+
+// Names of the rules in the order they run, as accepted by --rules
+pub const RULE_NAMES: [&str; 6] = ["unknown-airport", "self-loop", "duplicate", "inactive-airline", "unknown-equipment", "implausible-leg"];
+
+// Rows that passed every rule and rows annotated with each rule they failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationResult {
+    pub header: Vec<String>,
+    pub clean: Vec<RouteRecord>,
+    // (route, [(rule name, reason)])
+    pub rejected: Vec<(RouteRecord, Vec<(&'static str, String)>)>,
+}
+
+impl ValidationResult {
+    // Number of rejected rows failing each rule
+    pub fn failures_by_rule(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for (_, failures) in &self.rejected {
+            for (rule, _) in failures {
+                *counts.entry(*rule).or_default() += 1;
+            }
+        }
+        counts
+    }
+}
+
+// Read every column of a routes file along with its header, which is empty for headerless formats
+pub fn read_route_records(filename: &str, format: InputFormat) -> Result<(Vec<String>, Vec<RouteRecord>), LoadError> {
+    let columns = RouteColumns::of(filename, format)?;
+    let mut csv_reader = ReaderBuilder::new()
+        .flexible(true)
+        .has_headers(format.has_header())
+        .from_path(filename)
+        .map_err(|error| LoadError::from_csv(filename, error))?;
+    let header = if format.has_header() {
+        csv_reader.headers().map_err(|error| LoadError::from_csv(filename, error))?.iter().map(String::from).collect()
+    } else {
+        vec![]
+    };
+    let mut records = Vec::new();
+    for result in csv_reader.records() {
+        let record = result.map_err(|error| LoadError::from_csv(filename, error))?;
+        let line = record.position().map(|position| position.line() as usize).unwrap_or(0);
+        records.push(RouteRecord { line, fields: record.iter().map(String::from).collect(), columns });
+    }
+    Ok((header, records))
+}
+
+// Run every rule on every route; a route is clean only if it passes them all
+pub fn validate_routes(header: Vec<String>, routes: Vec<RouteRecord>, rules: &mut [Box<dyn RouteRule + '_>]) -> ValidationResult {
+    let mut result = ValidationResult { header, clean: vec![], rejected: vec![] };
+    for route in routes {
+        let failures: Vec<(&'static str, String)> =
+            rules.iter_mut().filter_map(|rule| rule.check(&route).map(|reason| (rule.name(), reason))).collect();
+        if failures.is_empty() {
+            result.clean.push(route);
+        } else {
+            result.rejected.push((route, failures));
+        }
+    }
+    result
+}
+
+// Clean rows as they were read, and rejected rows with extra columns for their line, the failed rules and why;
+// the header row is only written when the input had one
+pub fn write_validation_csv(result: &ValidationResult, clean_file: &str, rejects_file: &str) -> Result<(), Box<dyn Error>> {
+    let has_header = !result.header.is_empty();
+    let mut clean = WriterBuilder::new().flexible(true).from_path(clean_file)?;
+    if has_header {
+        clean.write_record(&result.header)?;
+    }
+    for route in &result.clean {
+        clean.write_record(&route.fields)?;
+    }
+    clean.flush()?;
+
+    let mut rejects = WriterBuilder::new().flexible(true).from_path(rejects_file)?;
+    if has_header {
+        rejects.write_record(result.header.iter().map(String::as_str).chain(["line", "rules", "reasons"]))?;
+    }
+    for (route, failures) in &result.rejected {
+        let rules: Vec<&str> = failures.iter().map(|(rule, _)| *rule).collect();
+        let reasons: Vec<&str> = failures.iter().map(|(_, reason)| reason.as_str()).collect();
+        let mut row = route.fields.clone();
+        if has_header {
+            row.resize(result.header.len(), String::new());
+        }
+        row.extend([route.line.to_string(), rules.join(";"), reasons.join("; ")]);
+        rejects.write_record(&row)?;
+    }
+    rejects.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use crate::aircraft;
+    use crate::aircraft::load_aircraft_from_csv;
+    use crate::load::LoadMode;
+    use crate::airlines;
+
+    #[test]
+    fn test_route_validation_rules() {
+        let mut airports: HashMap<String, Airport> = HashMap::new();
+        airports.insert("LHR".to_string(), Airport::new("London Heathrow Airport", "London", "United Kingdom", 51.4706, -0.461941));
+        airports.insert("CDG".to_string(), Airport::new("Charles de Gaulle International Airport", "Paris", "France", 49.012798, 2.55));
+        airports.insert("SYD".to_string(), Airport::new("Sydney Kingsford Smith International Airport", "Sydney", "Australia", -33.9461, 151.177));
+        let mut airlines: HashMap<String, airlines::Airline> = HashMap::new();
+        airlines.insert("9".to_string(), airlines::Airline { name: "Gone Air".to_string(), iata: "GA".to_string(), icao: String::new(), country: String::new(), active: false });
+        let mut aircraft: HashMap<String, aircraft::Aircraft> = HashMap::new();
+        for code in ["320", "AT7"] {
+            aircraft.insert(code.to_string(), aircraft::Aircraft { name: code.to_string(), iata: code.to_string(), icao: String::new() });
+        }
+
+        let rows = [
+            "0,BA,1,LHR,1,CDG,2,,0,320",
+            "1,BA,1,LHR,1,XXX,2,,0,320",
+            "2,BA,1,LHR,1,LHR,1,,0,320",
+            "3,BA,1,LHR,1,CDG,2,,0,320",
+            "4,GA,9,CDG,2,LHR,1,,0,320",
+            "5,BA,1,CDG,2,LHR,1,,0,XYZ",
+            "6,BA,1,LHR,1,SYD,3,,0,AT7",
+        ];
+        let indexed = RouteColumns::of("routes.csv", InputFormat::Indexed).unwrap();
+        let records: Vec<RouteRecord> = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| RouteRecord { line: index + 2, fields: row.split(',').map(String::from).collect(), columns: indexed })
+            .collect();
+        let mut rules: Vec<Box<dyn RouteRule>> = vec![
+            Box::new(UnknownAirports { airports: &airports }),
+            Box::new(SelfLoops),
+            Box::new(DuplicateRows::default()),
+            Box::new(InactiveAirlines { airlines: &airlines }),
+            Box::new(UnknownEquipment { aircraft: &aircraft }),
+            Box::new(ImplausibleLegs { airports: &airports }),
+        ];
+
+        let result = validate_routes(vec![], records, &mut rules);
+        assert_eq!(result.clean.len(), 1);
+        assert_eq!(result.clean[0].line, 2);
+        let failed: Vec<(usize, Vec<&str>)> =
+            result.rejected.iter().map(|(route, failures)| (route.line, failures.iter().map(|(rule, _)| *rule).collect())).collect();
+        assert_eq!(
+            failed,
+            vec![
+                (3, vec!["unknown-airport"]),
+                (4, vec!["self-loop"]),
+                (5, vec!["duplicate"]),
+                (6, vec!["inactive-airline"]),
+                (7, vec!["unknown-equipment"]),
+                (8, vec!["implausible-leg"]),
+            ]
+        );
+        assert_eq!(result.failures_by_rule().get("duplicate"), Some(&1));
+    }
+
+    #[test]
+    fn test_equipment_known_from_shipped_airplanes() {
+        let aircraft = load_aircraft_from_csv("airplanes.csv", LoadMode::Strict).unwrap().data;
+        // Winglet and family codes missing from airplanes.csv are still real aircraft
+        for code in ["73H", "73W", "DH8"] {
+            assert!(!aircraft.contains_key(code), "{} is now in airplanes.csv", code);
+        }
+
+        let mut rule = UnknownEquipment { aircraft: &aircraft };
+        let columns = RouteColumns::of("routes.csv", InputFormat::Indexed).unwrap();
+        let route = |equipment: &str| RouteRecord {
+            line: 2,
+            fields: format!("0,BA,1,LHR,1,CDG,2,,0,{}", equipment).split(',').map(String::from).collect(),
+            columns,
+        };
+        for equipment in ["320 738", "73H 73W", "DH8 CRJ ERJ", "B738", "M80 32S"] {
+            assert_eq!(rule.check(&route(equipment)), None, "{}", equipment);
+        }
+        assert_eq!(rule.check(&route("73H XYZ")), Some("unknown equipment XYZ".to_string()));
+        // Codes shaped like a variant or family are not accepted unless one of the lists names them
+        assert_eq!(rule.check(&route("73Z 74Z")), Some("unknown equipment 73Z 74Z".to_string()));
+    }
+
+    #[test]
+    fn test_validate_openflights_routes() {
+        let dir = env::temp_dir();
+        let (routes, clean, rejects) = (
+            dir.join("test_validate_openflights_routes.dat"),
+            dir.join("test_validate_openflights_clean.dat"),
+            dir.join("test_validate_openflights_rejects.dat"),
+        );
+        std::fs::write(&routes, "BA,1355,LHR,507,CDG,1382,,0,320\nBA,1355,LHR,507,LHR,507,,0,320\n").unwrap();
+
+        let (header, records) = read_route_records(routes.to_str().unwrap(), InputFormat::OpenFlights).unwrap();
+        assert!(header.is_empty());
+        assert_eq!((records[0].line, records[0].airline(), records[0].airline_id()), (1, "BA", "1355"));
+        assert_eq!((records[0].from(), records[0].to(), records[0].equipment()), ("LHR", "CDG", vec!["320"]));
+
+        let mut rules: Vec<Box<dyn RouteRule>> = vec![Box::new(SelfLoops), Box::new(DuplicateRows::default())];
+        let result = validate_routes(header, records, &mut rules);
+        write_validation_csv(&result, clean.to_str().unwrap(), rejects.to_str().unwrap()).unwrap();
+        assert_eq!(std::fs::read_to_string(&clean).unwrap(), "BA,1355,LHR,507,CDG,1382,,0,320\n");
+        assert_eq!(std::fs::read_to_string(&rejects).unwrap(), "BA,1355,LHR,507,LHR,507,,0,320,2,self-loop,route from LHR to itself\n");
+
+        assert!(matches!(read_route_records("airports.csv", InputFormat::OurAirports), Err(LoadError::UnsupportedFormat { .. })));
+        for path in [routes, clean, rejects] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}